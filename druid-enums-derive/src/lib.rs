@@ -0,0 +1,1320 @@
+use heck::CamelCase;
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, parse_quote, Fields, GenericParam, Generics, Ident, Type};
+
+mod match_widget;
+mod parse;
+mod static_matcher;
+mod widget_enum;
+use parse::{expr_style, MatcherDerive, MatcherVariant, Missing};
+
+#[proc_macro_derive(Matcher, attributes(matcher))]
+pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    // TODO when we generate a name that isn't a valid ident or is a keyword, generate a different
+    // name rather than panicking.
+    let input = parse_macro_input!(input as MatcherDerive);
+    let druid_enums = input.resolve_crate_path();
+
+    let visibility = &input.visibility;
+    let enum_name = &input.enum_name;
+    let enum_path = input.resolve_enum_path();
+    let enum_pat = expr_style(&enum_path);
+    let data_ty = input.resolve_data_type();
+    let matcher_name = input.resolve_matcher_name();
+    let druid = input.resolve_druid_path();
+    let kind_name = input.resolve_kind_name();
+    let data_generics = input.resolve_data_generics();
+    let (impl_generics, ty_generics, where_clause) = data_generics.split_for_impl();
+    let (enum_generics, _, enum_where_clause) = input.generics.split_for_impl();
+
+    let kind_variants = input.variants.iter().map(|variant| &variant.name);
+
+    let kind_of_match = input.variants.iter().map(|variant| {
+        let variant_name = &variant.name;
+        let any_pattern = any_of(variant);
+        quote!(#enum_pat::#variant_name #any_pattern => #kind_name::#variant_name)
+    });
+
+    let kind_all = input.variants.iter().map(|variant| {
+        let variant_name = &variant.name;
+        quote!(#kind_name::#variant_name)
+    });
+
+    let kind_names = input
+        .variants
+        .iter()
+        .map(|variant| variant.name.to_string());
+
+    let kind_title_fn = if input.variants.iter().any(|variant| variant.title.is_some()) {
+        let kind_title_match = input.variants.iter().map(|variant| {
+            let variant_name = &variant.name;
+            match &variant.title {
+                Some(title) => quote!(#kind_name::#variant_name => Some(#title)),
+                None => quote!(#kind_name::#variant_name => None),
+            }
+        });
+        quote! {
+            fn title(self) -> Option<&'static str> {
+                match self {
+                    #(#kind_title_match,)*
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    let menu_items = input.variants.iter().filter_map(|variant| {
+        let variant_name = &variant.name;
+        match nested_ty(variant) {
+            // The menu of the kind can't name the generic parameters of the enum.
+            Some(ty) if !mentions_generics(ty, &input.generics) => Some(quote! {
+                #druid_enums::__private::VariantMenuItem::Nested(
+                    #kind_name::#variant_name,
+                    <#ty as #druid_enums::MatcherEnum>::menu::<T>,
+                )
+            }),
+            _ if variant.is_switchable() => Some(quote! {
+                #druid_enums::__private::VariantMenuItem::Switch(#kind_name::#variant_name)
+            }),
+            _ => None,
+        }
+    });
+
+    let apply_switch_fn = apply_switch_fn(&input);
+    let route_fns = route_fns(&input);
+    let wizard_fns = wizard_fns(&input);
+    let wizard_impl = wizard_impl(&input);
+    let common_lenses = common_lenses(&input);
+
+    let struct_fields = input.slot_variants().into_iter().map(|variant| {
+        let builder_name = variant.resolve_slot_name();
+        let variant_ty = type_of(&input, variant);
+        quote!(#builder_name: Option<#druid::WidgetPod<#variant_ty, Box<dyn #druid::Widget<#variant_ty>>>>)
+    });
+
+    let struct_defaults = input.slot_variants().into_iter().map(|variant| {
+        let builder_name = variant.resolve_slot_name();
+        quote!(#builder_name: None)
+    });
+
+    let builder_fns = input.slot_variants().into_iter().map(|variant| {
+        let builder_name = variant.resolve_slot_name();
+        let variant_ty = type_of(&input, variant);
+        match nested_builder(&input, variant) {
+            Some((build_param, widget, bound)) => quote! {
+                pub fn #builder_name<__W: #druid::Widget<#variant_ty> + 'static>(
+                    mut self,
+                    #build_param,
+                ) -> Self #bound {
+                    self.#builder_name = Some(#druid::WidgetPod::new(Box::new(#widget)));
+                    self
+                }
+            },
+            None => quote! {
+                pub fn #builder_name(mut self, widget: impl #druid::Widget<#variant_ty> + 'static) -> Self {
+                    self.#builder_name = Some(#druid::WidgetPod::new(Box::new(widget)));
+                    self
+                }
+            },
+        }
+    });
+
+    let replace_fns = input.slot_variants().into_iter().map(|variant| {
+        let builder_name = variant.resolve_slot_name();
+        let replace_name = format_ident!("replace_{}", builder_name);
+        let variant_name = &variant.name;
+        let variant_ty = type_of(&input, variant);
+        quote! {
+            pub fn #replace_name(
+                &mut self,
+                ctx: &mut #druid::EventCtx,
+                widget: impl #druid::Widget<#variant_ty> + 'static,
+            ) {
+                let widget = #druid_enums::box_widget(widget);
+                let _ = #druid_enums::Matcher::replace_widget_boxed(self, ctx, #kind_name::#variant_name, widget);
+            }
+        }
+    });
+
+    let has_widget_match = input.variants.iter().map(|variant| {
+        let builder_name = variant.resolve_slot_name();
+        let variant_name = &variant.name;
+        match variant.skip {
+            true => quote!(#kind_name::#variant_name => true),
+            false => quote!(#kind_name::#variant_name => self.#builder_name.is_some()),
+        }
+    });
+
+    let set_widget_match = input.variants.iter().map(|variant| {
+        let builder_name = variant.resolve_slot_name();
+        let variant_name = &variant.name;
+        if variant.skip {
+            return quote!(#kind_name::#variant_name => Err(widget));
+        }
+        quote! {
+            #kind_name::#variant_name => {
+                #druid_enums::__private::set_boxed_widget(&mut self.#builder_name, widget)
+            }
+        }
+    });
+
+    let widget_added_checks = input.slot_variants().into_iter().filter_map(|variant| {
+        let builder_name = variant.resolve_slot_name();
+        let on_missing = on_missing_widget(&input, variant)?;
+        Some(quote! {
+            if self.default_.is_none() && self.#builder_name.is_none() {
+                #on_missing
+            }
+        })
+    });
+    // Variants without a widget leave the visitor to the default widget.
+    let active_child_match = |mutable| -> Vec<TokenStream> {
+        input
+            .variants
+            .iter()
+            .map(|variant| {
+                if variant.skip {
+                    let enum_pat = expr_style(&input.resolve_enum_path());
+                    let variant_name = &variant.name;
+                    let any = any_of(variant);
+                    return quote!(#enum_pat::#variant_name #any => {});
+                }
+                let builder_name = variant.resolve_slot_name();
+                let (pattern, visit) = visit_variant(&input, variant, quote!(child), mutable);
+                quote! {
+                    #pattern => if let Some(child) = &mut self.#builder_name {
+                        return Some(#visit);
+                    }
+                }
+            })
+            .collect()
+    };
+    let active_child_ref_match = active_child_match(false);
+    let active_child_mut_match = active_child_match(true);
+    let (active_enum_ref, active_enum_mut) =
+        (active_enum(&input, false), active_enum(&input, true));
+
+    let introspection_fns = introspection_fns(&input, Some(quote!(self.default_.is_some())));
+    let presentation_fns = presentation_fns(&input);
+
+    let widget_impl = widget_impl(
+        &input,
+        &data_generics,
+        quote! {
+            if let #druid::Event::Command(cmd) = event {
+                if let Some(replacement) = cmd.get(#druid_enums::REPLACE_VARIANT_WIDGET) {
+                    let replaced = replacement.apply(|kind, widget| {
+                        #druid_enums::Matcher::replace_widget_boxed(self, ctx, kind, widget)
+                    });
+                    if replaced {
+                        ctx.set_handled();
+                        return;
+                    }
+                }
+            }
+        },
+        quote!(#(#widget_added_checks)*),
+    );
+
+    let kind_output = quote! {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #visibility enum #kind_name {
+            #(#kind_variants,)*
+        }
+
+        impl #kind_name {
+            /// Submitted to the window whenever a matcher switches between variants.
+            pub const VARIANT_CHANGED: #druid::Selector<#druid_enums::VariantChanged<#kind_name>> =
+                #druid::Selector::new(concat!(module_path!(), "::", stringify!(#kind_name), ".variant-changed"));
+
+            /// Switches matchers of the enum to the variant, if its data can be created,
+            /// i.e. for unit variants and variants with `#[matcher(init = ...)]`.
+            ///
+            /// Matchers of enums containing this one as a `#[matcher(nested)]` variant apply it too.
+            pub const SWITCH_VARIANT: #druid::Selector<#kind_name> =
+                #druid::Selector::new(concat!(module_path!(), "::", stringify!(#kind_name), ".switch-variant"));
+
+            /// A menu with an item submitting `SWITCH_VARIANT` for each variant that can be switched to,
+            /// and a submenu for each `#[matcher(nested)]` variant.
+            pub fn menu<T: #druid::Data>(title: &str) -> #druid_enums::VariantMenu<T> {
+                #druid_enums::__private::variant_menu(title, Self::SWITCH_VARIANT, vec![#(#menu_items,)*])
+            }
+
+            #apply_switch_fn
+            #route_fns
+            #wizard_fns
+
+            pub fn of #enum_generics (data: &#enum_path) -> Self #enum_where_clause {
+                match data {
+                    #(#kind_of_match,)*
+                }
+            }
+        }
+
+        impl #druid_enums::VariantKind for #kind_name {
+            const ALL: &'static [Self] = &[#(#kind_all,)*];
+
+            fn name(self) -> &'static str {
+                const NAMES: &[&str] = &[#(#kind_names,)*];
+                NAMES[self as usize]
+            }
+
+            #kind_title_fn
+        }
+
+        #wizard_impl
+        #common_lenses
+    };
+    if input.static_dispatch {
+        let matcher_output = static_matcher::expand(&input);
+        return quote!(#kind_output #matcher_output).into();
+    }
+
+    let matcher_enum_impl = matcher_enum_impl(&input, quote!(#matcher_name #ty_generics));
+
+    let output = quote! {
+        #kind_output
+        #matcher_enum_impl
+
+        impl #impl_generics #enum_name #ty_generics #where_clause {
+            pub fn matcher() -> #matcher_name #ty_generics {
+                #matcher_name::new()
+            }
+        }
+
+        #visibility struct #matcher_name #impl_generics #where_clause {
+            #(#struct_fields,)*
+            default_: Option<#druid::WidgetPod<#data_ty, Box<dyn #druid::Widget<#data_ty>>>>,
+            kind_: Option<#kind_name>,
+            present_: #druid_enums::__private::Presentation,
+        }
+
+        impl #impl_generics #matcher_name #ty_generics #where_clause {
+            pub fn new() -> Self {
+                Self {
+                    #(#struct_defaults,)*
+                    default_: None,
+                    kind_: None,
+                    present_: Default::default(),
+                }
+            }
+            pub fn default(mut self, widget: impl #druid::Widget<#data_ty> + 'static) -> Self {
+                self.default_ = Some(#druid::WidgetPod::new(Box::new(widget)));
+                self
+            }
+            pub fn default_empty(mut self) -> Self {
+                self.default_ = Some(#druid::WidgetPod::new(Box::new(#druid::widget::SizedBox::empty())));
+                self
+            }
+            #(#builder_fns)*
+            #(#replace_fns)*
+            #introspection_fns
+            #presentation_fns
+
+            fn with_active_child<V: #druid_enums::__private::VisitChild>(
+                &mut self,
+                data: &#data_ty,
+                visitor: V,
+            ) -> Option<V::Output> {
+                match #active_enum_ref {
+                    #(#active_child_ref_match)*
+                }
+                self.default_.as_mut().map(|child| visitor.visit(child, data))
+            }
+
+            fn with_active_child_mut<V: #druid_enums::__private::VisitChildMut>(
+                &mut self,
+                data: &mut #data_ty,
+                visitor: V,
+            ) -> Option<V::Output> {
+                match #active_enum_mut {
+                    #(#active_child_mut_match)*
+                }
+                self.default_.as_mut().map(|child| visitor.visit_mut(child, data))
+            }
+        }
+
+        impl #impl_generics #druid_enums::Matcher for #matcher_name #ty_generics #where_clause {
+            type Enum = #enum_path;
+            type Kind = #kind_name;
+
+            fn active_kind(&self) -> Option<#kind_name> {
+                self.kind_
+            }
+            fn has_widget_for(&self, kind: #kind_name) -> bool {
+                match kind {
+                    #(#has_widget_match,)*
+                }
+            }
+            fn set_widget_boxed(
+                &mut self,
+                kind: #kind_name,
+                widget: Box<dyn ::std::any::Any>,
+            ) -> Result<(), Box<dyn ::std::any::Any>> {
+                match kind {
+                    #(#set_widget_match,)*
+                }
+            }
+        }
+
+        #widget_impl
+    };
+    output.into()
+}
+
+/// Matches a widget to each variant of an enum, without deriving `Matcher`.
+///
+/// ```ignore
+/// match_widget! { AppState,
+///     Login(_) => login_ui(),
+///     Main(_) => main_ui(),
+///     _ => fallback_ui(),
+/// }
+/// ```
+///
+/// The field of a variant is the data of its widget, so arms match it with `_` or `..`
+/// instead of binding it.
+#[proc_macro]
+pub fn match_widget(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as match_widget::MatchWidget);
+    match_widget::expand(input).into()
+}
+
+/// Implements `Widget<T>` for an enum with a widget over `T` in each variant.
+///
+/// ```ignore
+/// #[derive(WidgetEnum)]
+/// enum Body {
+///     Text(Label<AppData>),
+///     Image(Image),
+/// }
+/// ```
+#[proc_macro_derive(WidgetEnum, attributes(matcher))]
+pub fn derive_widget_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as widget_enum::WidgetEnum);
+    widget_enum::expand(input).into()
+}
+
+/// Returns the `T` in `Widget<T>` for the variant.
+fn type_of(input: &MatcherDerive, variant: &MatcherVariant) -> TokenStream {
+    if variant.full {
+        let data_ty = input.resolve_data_type();
+        return quote!(#data_ty);
+    }
+    let fields_ty = variant.fields_type();
+    match &input.context {
+        Some(context) => quote!((#context, #fields_ty)),
+        None => fields_ty,
+    }
+}
+
+/// Returns (pattern to match for, `data` param for the widget).
+fn data_of(variant: &MatcherVariant) -> (TokenStream, TokenStream) {
+    match &variant.fields {
+        Fields::Unit => (quote!(), quote!(&mut ())),
+        Fields::Unnamed(fields) if fields.unnamed.is_empty() => (quote!(()), quote!(&mut ())),
+        Fields::Unnamed(fields) => {
+            let names: Vec<syn::Ident> = fields
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(i, _)| format_ident!("p{}", i))
+                .collect();
+            (quote!((#(#names),*)), quote!((#(#names),*)))
+        }
+        Fields::Named(_) => unreachable!(),
+    }
+}
+
+/// Returns the pattern matching the variant, ignoring its fields.
+fn any_of(variant: &MatcherVariant) -> TokenStream {
+    match &variant.fields {
+        Fields::Unit => quote!(),
+        _ => quote!((..)),
+    }
+}
+
+/// Returns the type of the enum inside a `#[matcher(nested)]` variant.
+fn nested_ty(variant: &MatcherVariant) -> Option<&Type> {
+    match &variant.fields {
+        Fields::Unnamed(fields) if variant.nested => Some(&fields.unnamed[0].ty),
+        _ => None,
+    }
+}
+
+/// Returns whether the tokens name one of the generic parameters.
+fn mentions_generics(ty: &Type, generics: &Generics) -> bool {
+    fn mentions(tokens: TokenStream, names: &[String]) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => names.contains(&ident.to_string()),
+            TokenTree::Group(group) => mentions(group.stream(), names),
+            _ => false,
+        })
+    }
+    let names: Vec<String> = generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Type(param) => param.ident.to_string(),
+            GenericParam::Lifetime(param) => param.lifetime.ident.to_string(),
+            GenericParam::Const(param) => param.ident.to_string(),
+        })
+        .collect();
+    mentions(quote!(#ty), &names)
+}
+
+/// Returns the generics with a `MatcherEnum` bound for the type of each nested variant.
+fn with_nested_bounds(input: &MatcherDerive, generics: &Generics) -> Generics {
+    let druid_enums = input.resolve_crate_path();
+    let mut generics = generics.clone();
+    let where_clause = generics.make_where_clause();
+    for ty in input.variants.iter().filter_map(nested_ty) {
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: #druid_enums::MatcherEnum));
+    }
+    generics
+}
+
+/// Returns (builder parameter, widget built by it, where clause) for a `#[matcher(nested)]`
+/// variant, whose builder hands the matcher of the nested enum to a closure.
+fn nested_builder(
+    input: &MatcherDerive,
+    variant: &MatcherVariant,
+) -> Option<(TokenStream, TokenStream, TokenStream)> {
+    let druid_enums = input.resolve_crate_path();
+    let ty = nested_ty(variant)?;
+    let matcher_enum = quote!(<#ty as #druid_enums::MatcherEnum>);
+    let bound = match mentions_generics(ty, &input.generics) {
+        true => quote!(where #ty: #druid_enums::MatcherEnum),
+        false => quote!(),
+    };
+    Some((
+        quote!(build: impl FnOnce(#matcher_enum::Matcher) -> __W),
+        quote!(build(#matcher_enum::matcher())),
+        bound,
+    ))
+}
+
+/// Returns the expression creating the data of the variant, if it can be created on its own.
+fn new_data(input: &MatcherDerive, variant: &MatcherVariant) -> Option<TokenStream> {
+    let enum_pat = expr_style(&input.resolve_enum_path());
+    let variant_name = &variant.name;
+    match &variant.init {
+        Some(init) => Some(quote!(#enum_pat::#variant_name(#init()))),
+        None if matches!(variant.fields, Fields::Unit) => Some(quote!(#enum_pat::#variant_name)),
+        None if variant.fields.is_empty() => Some(quote!(#enum_pat::#variant_name())),
+        None => None,
+    }
+}
+
+/// Returns `{Enum}Kind::route`, `from_route` and `apply_route`, see `MatcherEnum`.
+fn route_fns(input: &MatcherDerive) -> TokenStream {
+    let druid_enums = input.resolve_crate_path();
+    let enum_path = input.resolve_enum_path();
+    let enum_pat = expr_style(&enum_path);
+    let generics = with_nested_bounds(input, &input.generics);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let segments = input
+        .variants
+        .iter()
+        .map(|variant| variant.resolve_builder_name().to_string());
+
+    let nested_route_match = input.variants.iter().filter_map(|variant| {
+        let variant_name = &variant.name;
+        let ty = nested_ty(variant)?;
+        Some(quote! {
+            #enum_pat::#variant_name(inner) => {
+                route.extend(<#ty as #druid_enums::MatcherEnum>::route(inner));
+            }
+        })
+    });
+
+    let from_route_match = input.variants.iter().flat_map(|variant| {
+        let variant_name = &variant.name;
+        let segment = variant.resolve_builder_name().to_string();
+        let nested = nested_ty(variant).map(|ty| {
+            quote! {
+                (#segment, rest) => {
+                    <#ty as #druid_enums::MatcherEnum>::from_route(rest).map(#enum_pat::#variant_name)
+                }
+            }
+        });
+        let own = new_data(input, variant).map(|new_data| {
+            quote!((#segment, []) => Some(#new_data))
+        });
+        own.into_iter().chain(nested)
+    });
+
+    let nested_apply_route_match = input.variants.iter().filter_map(|variant| {
+        let variant_name = &variant.name;
+        let segment = variant.resolve_builder_name().to_string();
+        let ty = nested_ty(variant)?;
+        Some(quote! {
+            (Some((&#segment, rest)), #enum_pat::#variant_name(inner)) if !rest.is_empty() => {
+                <#ty as #druid_enums::MatcherEnum>::apply_route(inner, rest)
+            }
+        })
+    });
+
+    quote! {
+        /// The builder name of each variant, in the order of `ALL`.
+        const SEGMENTS: &'static [&'static str] = &[#(#segments,)*];
+
+        /// The builder names of the variant of `data` and of the variants nested in it.
+        pub fn route #impl_generics (data: &#enum_path) -> Vec<&'static str> #where_clause {
+            let mut route = vec![Self::SEGMENTS[Self::of(data) as usize]];
+            match data {
+                #(#nested_route_match)*
+                _ => {}
+            }
+            route
+        }
+
+        /// Creates the data for a route, see `MatcherEnum::from_route`.
+        pub fn from_route #impl_generics (route: &[&str]) -> Option<#enum_path> #where_clause {
+            let (&segment, rest) = route.split_first()?;
+            match (segment, rest) {
+                #(#from_route_match,)*
+                _ => None,
+            }
+        }
+
+        /// Navigates `data` to the route, see `MatcherEnum::apply_route`.
+        pub fn apply_route #impl_generics (data: &mut #enum_path, route: &[&str]) -> bool #where_clause {
+            match (route.split_first(), &mut *data) {
+                #(#nested_apply_route_match,)*
+                (Some((&segment, [])), data) if segment == Self::SEGMENTS[Self::of(data) as usize] => true,
+                _ => match Self::from_route(route) {
+                    Some(new_data) => {
+                        *data = new_data;
+                        true
+                    }
+                    None => false,
+                },
+            }
+        }
+    }
+}
+
+/// Returns the step commands and functions of `{Enum}Kind`, for `#[matcher(wizard)]`.
+fn wizard_fns(input: &MatcherDerive) -> TokenStream {
+    if !input.wizard {
+        return quote!();
+    }
+    let enum_path = input.resolve_enum_path();
+    let enum_pat = expr_style(&enum_path);
+    let druid = input.resolve_druid_path();
+    let kind_name = input.resolve_kind_name();
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+
+    let step_index_match = input.variants.iter().enumerate().map(|(index, variant)| {
+        let variant_name = &variant.name;
+        quote!(#kind_name::#variant_name => #index)
+    });
+
+    // Every step can be created, this is checked while parsing.
+    let step_data = |variant| new_data(input, variant).unwrap();
+    let next_match = input.variants.iter().enumerate().map(|(index, variant)| {
+        let variant_name = &variant.name;
+        match input.variants.get(index + 1) {
+            Some(next) => {
+                let next_data = step_data(next);
+                quote!(#kind_name::#variant_name => Some(#next_data))
+            }
+            None => quote!(#kind_name::#variant_name => None),
+        }
+    });
+    let previous_match = input.variants.iter().enumerate().map(|(index, variant)| {
+        let variant_name = &variant.name;
+        match index.checked_sub(1).map(|index| &input.variants[index]) {
+            Some(previous) => {
+                let previous_data = step_data(previous);
+                quote!(#kind_name::#variant_name => Some(#previous_data))
+            }
+            None => quote!(#kind_name::#variant_name => None),
+        }
+    });
+
+    let validate_arms: Vec<TokenStream> = input
+        .variants
+        .iter()
+        .filter_map(|variant| {
+            let validate = variant.validate.as_ref()?;
+            let variant_name = &variant.name;
+            Some(quote!(#enum_pat::#variant_name(step) if !#validate(step) => return false))
+        })
+        .collect();
+    let validate = match validate_arms.is_empty() {
+        true => quote!(),
+        false => quote! {
+            #[allow(irrefutable_let_patterns)]
+            match &*data {
+                #(#validate_arms,)*
+                _ => {}
+            }
+        },
+    };
+
+    quote! {
+        /// Makes wizard matchers of the enum go to the next step, unless the current one
+        /// is the last or its `validate` function fails.
+        pub const NEXT_STEP: #druid::Selector = #druid::Selector::new(
+            concat!(module_path!(), "::", stringify!(#kind_name), ".next-step"),
+        );
+
+        /// Makes wizard matchers of the enum go to the previous step, unless the current one is the first.
+        pub const PREVIOUS_STEP: #druid::Selector = #druid::Selector::new(
+            concat!(module_path!(), "::", stringify!(#kind_name), ".previous-step"),
+        );
+
+        /// The position of the step, starting at 0.
+        pub fn step_index(self) -> usize {
+            match self {
+                #(#step_index_match,)*
+            }
+        }
+
+        /// Replaces `data` with the next step, returning whether there was one and the current step is valid.
+        pub fn next_step #impl_generics (data: &mut #enum_path) -> bool #where_clause {
+            #validate
+            let next = match Self::of(data) {
+                #(#next_match,)*
+            };
+            match next {
+                Some(next) => {
+                    *data = next;
+                    true
+                }
+                None => false,
+            }
+        }
+
+        /// Replaces `data` with the previous step, returning whether there was one.
+        pub fn previous_step #impl_generics (data: &mut #enum_path) -> bool #where_clause {
+            let previous = match Self::of(data) {
+                #(#previous_match,)*
+            };
+            match previous {
+                Some(previous) => {
+                    *data = previous;
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+}
+
+/// Returns a lens for each `common` field, and the constants on the enum to reach them by.
+fn common_lenses(input: &MatcherDerive) -> TokenStream {
+    if input.common.is_empty() {
+        return quote!();
+    }
+    let visibility = &input.visibility;
+    let enum_name = &input.enum_name;
+    let enum_path = input.resolve_enum_path();
+    let enum_pat = expr_style(&enum_path);
+    let druid = input.resolve_druid_path();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let lenses = input.common.iter().map(|common| {
+        let name = &common.name;
+        let ty = &common.ty;
+        let path = &common.path;
+        let lens_name = format_ident!("{}{}Lens", enum_name, name.to_string().to_camel_case());
+        let field_match = input
+            .variants
+            .iter()
+            .map(|variant| {
+                let variant_name = &variant.name;
+                let (data_pattern, data_values) = data_of(variant);
+                (quote!(#enum_pat::#variant_name #data_pattern), quote!((#data_values) #path))
+            })
+            .collect::<Vec<_>>();
+        let with_match = field_match
+            .iter()
+            .map(|(pattern, field)| quote!(#pattern => f(&#field)));
+        let with_mut_match = field_match
+            .iter()
+            .map(|(pattern, field)| quote!(#pattern => f(&mut #field)));
+        quote! {
+            /// Reaches the field of every variant of the enum.
+            #[derive(Debug, Clone, Copy)]
+            #visibility struct #lens_name;
+
+            impl #impl_generics #druid::Lens<#enum_path, #ty> for #lens_name #where_clause {
+                fn with<__V, __F: FnOnce(&#ty) -> __V>(&self, data: &#enum_path, f: __F) -> __V {
+                    match data {
+                        #(#with_match,)*
+                    }
+                }
+                fn with_mut<__V, __F: FnOnce(&mut #ty) -> __V>(&self, data: &mut #enum_path, f: __F) -> __V {
+                    match data {
+                        #(#with_mut_match,)*
+                    }
+                }
+            }
+
+            impl #impl_generics #enum_name #ty_generics #where_clause {
+                /// Lens to the field, whichever variant the enum is.
+                #[allow(non_upper_case_globals)]
+                pub const #name: #lens_name = #lens_name;
+            }
+        }
+    });
+    quote!(#(#lenses)*)
+}
+
+/// Returns the `Wizard` impl of the enum, for `#[matcher(wizard)]` unless it mirrors a foreign enum.
+fn wizard_impl(input: &MatcherDerive) -> TokenStream {
+    let druid_enums = input.resolve_crate_path();
+    if !input.wizard || input.remote.is_some() {
+        return quote!();
+    }
+    let enum_name = &input.enum_name;
+    let kind_name = input.resolve_kind_name();
+    let step_count = input.variants.len();
+    let data_generics = input.resolve_data_generics();
+    let (impl_generics, ty_generics, where_clause) = data_generics.split_for_impl();
+    quote! {
+        impl #impl_generics #druid_enums::Wizard for #enum_name #ty_generics #where_clause {
+            fn step_index(&self) -> usize {
+                #kind_name::of(self).step_index()
+            }
+            fn step_count() -> usize {
+                #step_count
+            }
+        }
+    }
+}
+
+/// Returns `{Enum}Kind::apply_switch`, which handles `SWITCH_VARIANT` for the enum and the
+/// enums nested in it.
+fn apply_switch_fn(input: &MatcherDerive) -> TokenStream {
+    let druid_enums = input.resolve_crate_path();
+    let enum_path = input.resolve_enum_path();
+    let enum_pat = expr_style(&enum_path);
+    let druid = input.resolve_druid_path();
+    let kind_name = input.resolve_kind_name();
+    let generics = with_nested_bounds(input, &input.generics);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let switch_match: Vec<TokenStream> = input
+        .variants
+        .iter()
+        .filter_map(|variant| {
+            let variant_name = &variant.name;
+            let new_data = new_data(input, variant)?;
+            Some(quote!(#kind_name::#variant_name => *data = #new_data))
+        })
+        .collect();
+    let switch_own = match switch_match.len() {
+        0 => quote!(return Self::of(data) == kind;),
+        n => {
+            let fallback = match n == input.variants.len() {
+                true => quote!(),
+                false => quote!(_ => return false,),
+            };
+            quote! {
+                if Self::of(data) != kind {
+                    match kind {
+                        #(#switch_match,)*
+                        #fallback
+                    }
+                }
+                return true;
+            }
+        }
+    };
+
+    let switch_nested = input.variants.iter().filter_map(|variant| {
+        let ty = nested_ty(variant)?;
+        let variant_name = &variant.name;
+        let apply_switch = quote!(<#ty as #druid_enums::MatcherEnum>::apply_switch);
+        let enter = variant.init.as_ref().map(|init| {
+            quote! {
+                else {
+                    let mut inner = #init();
+                    if #apply_switch(&mut inner, cmd) {
+                        *data = #enum_pat::#variant_name(inner);
+                        return true;
+                    }
+                }
+            }
+        });
+        Some(quote! {
+            if let #enum_pat::#variant_name(inner) = data {
+                if #apply_switch(inner, cmd) {
+                    return true;
+                }
+            } #enter
+        })
+    });
+
+    quote! {
+        /// Applies a `SWITCH_VARIANT` command of the enum or of an enum nested in it, see
+        /// `MatcherEnum::apply_switch`.
+        #[allow(irrefutable_let_patterns)]
+        pub fn apply_switch #impl_generics (data: &mut #enum_path, cmd: &#druid::Command) -> bool #where_clause {
+            if let Some(&kind) = cmd.get(Self::SWITCH_VARIANT) {
+                #switch_own
+            }
+            #(#switch_nested)*
+            false
+        }
+    }
+}
+
+/// Returns the `MatcherEnum` impl of the enum, unless it mirrors a foreign enum or has a context.
+fn matcher_enum_impl(input: &MatcherDerive, matcher_ty: TokenStream) -> TokenStream {
+    let druid_enums = input.resolve_crate_path();
+    if input.remote.is_some() || input.context.is_some() {
+        return quote!();
+    }
+    let enum_name = &input.enum_name;
+    let matcher_name = input.resolve_matcher_name();
+    let druid = input.resolve_druid_path();
+    let kind_name = input.resolve_kind_name();
+    let data_generics = input.resolve_data_generics();
+    let (_, ty_generics, _) = data_generics.split_for_impl();
+    let generics = with_nested_bounds(input, &data_generics);
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics #druid_enums::MatcherEnum for #enum_name #ty_generics #where_clause {
+            type Kind = #kind_name;
+            type Matcher = #matcher_ty;
+
+            fn matcher() -> Self::Matcher {
+                #matcher_name::new()
+            }
+            fn apply_switch(&mut self, cmd: &#druid::Command) -> bool {
+                #kind_name::apply_switch(self, cmd)
+            }
+            fn route(&self) -> Vec<&'static str> {
+                #kind_name::route(self)
+            }
+            fn from_route(route: &[&str]) -> Option<Self> {
+                #kind_name::from_route(route)
+            }
+            fn apply_route(&mut self, route: &[&str]) -> bool {
+                #kind_name::apply_route(self, route)
+            }
+            fn menu<__T: #druid::Data>(title: &str) -> #druid_enums::VariantMenu<__T> {
+                #kind_name::menu(title)
+            }
+        }
+    }
+}
+
+/// Returns the pattern of the variant, binding the fields its widget gets, and their names.
+fn projection(variant: &MatcherVariant) -> (TokenStream, Vec<Ident>) {
+    let names: Vec<Ident> = (0..variant.fields.len())
+        .map(|i| format_ident!("p{}", i))
+        .collect();
+    match (&variant.fields, variant.field) {
+        (Fields::Unit, _) => (quote!(), names),
+        (_, Some(field)) => {
+            let pattern = names.iter().enumerate().map(|(i, name)| match i == field {
+                true => quote!(#name),
+                false => quote!(_),
+            });
+            (quote!((#(#pattern),*)), vec![names[field].clone()])
+        }
+        _ => (quote!((#(#names),*)), names),
+    }
+}
+
+/// Returns clones of the fields the widget of a variant gets, as its data.
+fn cloned_fields(input: &MatcherDerive, variant: &MatcherVariant, names: &[Ident]) -> TokenStream {
+    let druid = input.resolve_druid_path();
+    match (names, &variant.lens) {
+        ([], _) => quote!(()),
+        ([name], None) => quote!(#name.clone()),
+        ([name], Some((lens, ty))) => {
+            quote!(#druid::Lens::with(&#lens, #name, |x: &#ty| x.clone()))
+        }
+        (names, _) => quote!((#(#names.clone()),*)),
+    }
+}
+
+/// Returns the statements writing `fields`, as created by `cloned_fields`, back to the variant.
+fn write_back_fields(
+    input: &MatcherDerive,
+    variant: &MatcherVariant,
+    names: &[Ident],
+) -> TokenStream {
+    let druid = input.resolve_druid_path();
+    let same = quote!(#druid::Data::same);
+    match (names, &variant.lens) {
+        ([], _) => quote!(),
+        ([name], None) => quote! {
+            if !#same(&fields, #name) {
+                *#name = fields;
+            }
+        },
+        ([name], Some((lens, ty))) => quote! {
+            #druid::Lens::with_mut(&#lens, #name, |x: &mut #ty| {
+                if !#same(x, &fields) {
+                    *x = fields;
+                }
+            });
+        },
+        (names, _) => {
+            let indices = (0..names.len()).map(syn::Index::from);
+            quote! {
+                #(if !#same(&fields.#indices, #names) {
+                    *#names = fields.#indices;
+                })*
+            }
+        }
+    }
+}
+
+/// Returns the enum within `data`, the data of the matcher, to match the variants against.
+fn active_enum(input: &MatcherDerive, mutable: bool) -> TokenStream {
+    match (&input.context, mutable) {
+        (Some(_), false) => quote!(&data.1),
+        (Some(_), true) => quote!(&mut data.1),
+        (None, _) => quote!(data),
+    }
+}
+
+/// Returns the pattern matching the variant in the enum returned by `active_enum`, and
+/// an expression visiting `child` with the data of the variant, taken from the pattern.
+///
+/// With `mutable`, `data` is a mutable reference and `visitor` a `VisitChildMut`,
+/// otherwise a shared reference and a `VisitChild`.
+fn visit_variant(
+    input: &MatcherDerive,
+    variant: &MatcherVariant,
+    child: TokenStream,
+    mutable: bool,
+) -> (TokenStream, TokenStream) {
+    let (visit, reference) = match mutable {
+        true => (quote!(visit_mut), quote!(&mut)),
+        false => (quote!(visit), quote!(&)),
+    };
+    let enum_pat = expr_style(&input.resolve_enum_path());
+    let variant_name = &variant.name;
+    if variant.full {
+        let any = any_of(variant);
+        return (
+            quote!(#enum_pat::#variant_name #any),
+            quote!(visitor.#visit(#child, data)),
+        );
+    }
+    let druid_enums = input.resolve_crate_path();
+    let druid = input.resolve_druid_path();
+    let (pattern, names) = projection(variant);
+    let pattern = quote!(#enum_pat::#variant_name #pattern);
+    if input.context.is_some() {
+        return (
+            pattern,
+            context_visit(input, variant, &names, child, mutable),
+        );
+    }
+    let visit_fields = match (names.as_slice(), &variant.lens, mutable) {
+        ([], _, _) => quote!(visitor.#visit(#child, #reference ())),
+        ([name], None, false) if variant.deref => quote!(visitor.visit(#child, &**#name)),
+        ([name], None, true) if variant.deref => quote! {
+            #druid_enums::__private::DerefData::with_mut(#name, |content| visitor.visit_mut(#child, content))
+        },
+        ([name], None, _) => quote!(visitor.#visit(#child, #name)),
+        ([name], Some((lens, ty)), false) => quote! {
+            #druid::Lens::with(&#lens, #name, |x: &#ty| visitor.visit(#child, x))
+        },
+        ([name], Some((lens, ty)), true) => quote! {
+            #druid::Lens::with_mut(&#lens, #name, |x: &mut #ty| visitor.visit_mut(#child, x))
+        },
+        // The widget gets the fields as a tuple, which has to be created first.
+        (_, _, false) => {
+            let fields = cloned_fields(input, variant, &names);
+            quote!(visitor.visit(#child, &#fields))
+        }
+        (_, _, true) => {
+            let fields = cloned_fields(input, variant, &names);
+            let write_back = write_back_fields(input, variant, &names);
+            quote! {{
+                let mut fields = #fields;
+                let output = visitor.visit_mut(#child, &mut fields);
+                #write_back
+                output
+            }}
+        }
+    };
+    (pattern, visit_fields)
+}
+
+/// Like `visit_variant`, for enums with a context, whose variants get a `(Context, Fields)`.
+fn context_visit(
+    input: &MatcherDerive,
+    variant: &MatcherVariant,
+    names: &[Ident],
+    child: TokenStream,
+    mutable: bool,
+) -> TokenStream {
+    let druid = input.resolve_druid_path();
+    let fields = cloned_fields(input, variant, names);
+    if !mutable {
+        return quote!(visitor.visit(#child, &(data.0.clone(), #fields)));
+    }
+    let write_back = write_back_fields(input, variant, names);
+    quote! {{
+        let mut child_data = (data.0.clone(), #fields);
+        let output = visitor.visit_mut(#child, &mut child_data);
+        let (context, fields) = child_data;
+        #write_back
+        if !#druid::Data::same(&context, &data.0) {
+            data.0 = context;
+        }
+        output
+    }}
+}
+
+/// Returns what to do about a variant without a widget, once the matcher is added to the widget tree.
+fn on_missing_widget(input: &MatcherDerive, variant: &MatcherVariant) -> Option<TokenStream> {
+    let druid_enums = input.resolve_crate_path();
+    let enum_name = &input.enum_name;
+    let matcher_name = input.resolve_matcher_name();
+    let druid = input.resolve_druid_path();
+    let builder_name = variant.resolve_slot_name();
+    let variant_name = &variant.name;
+    match input.missing {
+        Missing::Warn => Some(quote! {
+            #druid_enums::__private::widget_missing(stringify!(#matcher_name), stringify!(#builder_name), widget_id);
+        }),
+        Missing::Panic => Some(quote! {
+            #druid_enums::__private::panic_missing(stringify!(#matcher_name), stringify!(#builder_name));
+        }),
+        // Only boxed matchers can swap in a placeholder, this is checked while parsing.
+        Missing::Placeholder => Some(quote! {
+            self.#builder_name = Some(#druid::WidgetPod::new(Box::new(
+                #druid_enums::__private::Placeholder::new(stringify!(#enum_name), stringify!(#variant_name)),
+            )));
+        }),
+        Missing::Silent => None,
+    }
+}
+
+/// Returns the builder methods setting how the matcher is sized and switches variants.
+fn presentation_fns(input: &MatcherDerive) -> TokenStream {
+    let druid_enums = input.resolve_crate_path();
+    quote! {
+        /// Sets how the matcher is sized, by default like the widget of the active variant.
+        pub fn with_sizing(mut self, sizing: #druid_enums::Sizing) -> Self {
+            self.present_.sizing = sizing;
+            self
+        }
+        /// Sets how the matcher switches between variants, by default instantly.
+        pub fn with_transition(mut self, transition: #druid_enums::Transition) -> Self {
+            self.present_.transition = transition;
+            self
+        }
+    }
+}
+
+/// Returns the inherent methods telling which widgets the matcher has.
+fn introspection_fns(input: &MatcherDerive, has_default: Option<TokenStream>) -> TokenStream {
+    let druid_enums = input.resolve_crate_path();
+    let matcher_name = input.resolve_matcher_name();
+    let has_default = has_default.map(|has_default| quote!(#has_default ||));
+    quote! {
+        /// The names of the variants without a widget.
+        pub fn missing_variants(&self) -> Vec<&'static str> {
+            #druid_enums::missing_widgets(self)
+                .into_iter()
+                .map(#druid_enums::VariantKind::name)
+                .collect()
+        }
+        /// The name of the variant currently shown, if the matcher has seen any data yet.
+        pub fn active_variant_name(&self) -> Option<&'static str> {
+            self.kind_.map(#druid_enums::VariantKind::name)
+        }
+        /// Fails if a variant has neither a widget nor a default to fall back to.
+        pub fn try_build(self) -> Result<Self, #druid_enums::MissingVariants> {
+            let variants = self.missing_variants();
+            if #has_default variants.is_empty() {
+                Ok(self)
+            } else {
+                Err(#druid_enums::MissingVariants {
+                    matcher: stringify!(#matcher_name),
+                    variants,
+                })
+            }
+        }
+    }
+}
+
+/// Returns the `Widget` impl of the matcher, which dispatches through its `with_active_child`
+/// and `with_active_child_mut`, along with the checks it runs once added to the widget tree.
+fn widget_impl(
+    input: &MatcherDerive,
+    generics: &Generics,
+    event_prelude: TokenStream,
+    widget_added_checks: TokenStream,
+) -> TokenStream {
+    let druid_enums = input.resolve_crate_path();
+    let enum_name = &input.enum_name;
+    let data_ty = input.resolve_data_type();
+    let matcher_name = input.resolve_matcher_name();
+    let druid = input.resolve_druid_path();
+    let kind_name = input.resolve_kind_name();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // The enum within the data of the matcher.
+    let (enum_data, old_enum_data, enum_data_mut) = match input.context {
+        Some(_) => (quote!(&data.1), quote!(&old_data.1), quote!(&mut data.1)),
+        None => (quote!(data), quote!(old_data), quote!(data)),
+    };
+
+    let switch_variant = match input
+        .variants
+        .iter()
+        .any(|variant| variant.is_switchable() || variant.nested)
+    {
+        true => quote! {
+            if #kind_name::apply_switch(#enum_data_mut, cmd) {
+                ctx.set_handled();
+                return;
+            }
+        },
+        false => quote!(),
+    };
+
+    let wizard_steps = match input.wizard {
+        true => quote! {
+            if cmd.is(#kind_name::NEXT_STEP) && #kind_name::next_step(#enum_data_mut)
+                || cmd.is(#kind_name::PREVIOUS_STEP) && #kind_name::previous_step(#enum_data_mut)
+            {
+                ctx.set_handled();
+                return;
+            }
+        },
+        false => quote!(),
+    };
+
+    let set_window_title = match input.variants.iter().any(|variant| variant.title.is_some()) {
+        true => Some(quote!(#druid_enums::__private::set_window_title)),
+        false => None,
+    };
+    let title_on_added = set_window_title.as_ref().map(
+        |set_window_title| quote!(#set_window_title(ctx.window(), #kind_name::of(#enum_data));),
+    );
+    let title_on_switch = set_window_title
+        .as_ref()
+        .map(|_| quote!(ctx.set_window_title(new);));
+
+    quote! {
+        impl #impl_generics #matcher_name #ty_generics #where_clause {
+            /// Does what `#[matcher(missing = ...)]` asks for about the variants without a widget.
+            #[allow(unused_variables)]
+            fn check_missing_widgets(&mut self, widget_id: #druid::WidgetId) {
+                #widget_added_checks
+            }
+
+            /// Reacts to the data switching from the `old` to the `new` variant, if it did,
+            /// and returns whether it did.
+            fn update_variant(
+                &mut self,
+                ctx: &mut impl #druid_enums::__private::UpdateEffects,
+                old: #kind_name,
+                new: #kind_name,
+            ) -> bool {
+                if ctx.env_key_changed(&#druid_enums::DEBUG_PAINT_MATCHERS) {
+                    ctx.request_paint();
+                }
+                if old == new {
+                    return false;
+                }
+                #druid_enums::__private::variant_switched(stringify!(#matcher_name), old, new);
+                #title_on_switch
+                ctx.submit_variant_changed(#kind_name::VARIANT_CHANGED, old, new);
+                self.kind_ = Some(new);
+                if self.present_.start() {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_layout();
+                ctx.request_paint();
+                true
+            }
+        }
+
+        impl #impl_generics #druid::Widget<#data_ty> for #matcher_name #ty_generics #where_clause {
+            fn event(
+                &mut self,
+                ctx: &mut #druid::EventCtx,
+                event: &#druid::Event,
+                data: &mut #data_ty,
+                env: &#druid::Env
+            ) {
+                #event_prelude
+                self.present_.event(ctx, event);
+                if let #druid::Event::Command(cmd) = event {
+                    #switch_variant
+                    #wizard_steps
+                    if let Some(route) = cmd.get(#druid_enums::NAVIGATE_TO_ROUTE) {
+                        if #kind_name::apply_route(#enum_data_mut, &#druid_enums::split_route(route)) {
+                            ctx.set_handled();
+                            return;
+                        }
+                    }
+                }
+                if self.kind_ == Some(#kind_name::of(#enum_data)) {
+                    self.with_active_child_mut(data, #druid_enums::__private::VisitEvent { ctx, event, env });
+                }
+            }
+            fn lifecycle(
+                &mut self,
+                ctx: &mut #druid::LifeCycleCtx,
+                event: &#druid::LifeCycle,
+                data: &#data_ty,
+                env: &#druid::Env
+            ) {
+                self.kind_ = Some(#kind_name::of(#enum_data));
+                if let #druid::LifeCycle::WidgetAdded = event {
+                    self.check_missing_widgets(ctx.widget_id());
+                    #title_on_added
+                }
+                self.with_active_child(data, #druid_enums::__private::Visit::LifeCycle { ctx, event, env });
+            }
+            fn update(&mut self,
+                ctx: &mut #druid::UpdateCtx,
+                old_data: &#data_ty,
+                data: &#data_ty,
+                env: &#druid::Env
+            ) {
+                let switched = self.update_variant(
+                    ctx,
+                    #kind_name::of(#old_enum_data),
+                    #kind_name::of(#enum_data),
+                );
+                self.with_active_child(data, #druid_enums::__private::Visit::Update { ctx, env, switched });
+            }
+            fn layout(
+                &mut self,
+                ctx: &mut #druid::LayoutCtx,
+                bc: &#druid::BoxConstraints,
+                data: &#data_ty,
+                env: &#druid::Env
+            ) -> #druid::Size {
+                let _span = #druid_enums::__private::variant_span(stringify!(#matcher_name), "layout", self.kind_);
+                let size = self
+                    .with_active_child(data, #druid_enums::__private::Visit::Layout { ctx, bc, env })
+                    .unwrap_or_else(|| bc.min());
+                self.present_.size(size, bc)
+            }
+            fn paint(&mut self, ctx: &mut #druid::PaintCtx, data: &#data_ty, env: &#druid::Env) {
+                let _span = #druid_enums::__private::variant_span(stringify!(#matcher_name), "paint", self.kind_);
+                let present = self.present_;
+                present.paint(ctx, |ctx| {
+                    self.with_active_child(data, #druid_enums::__private::Visit::Paint { ctx, env });
+                });
+                #druid_enums::__private::debug_paint(ctx, env, stringify!(#enum_name), self.kind_);
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    Attribute, Expr, Ident, Path, Result, Token,
+};
+
+use crate::parse::{expr_style, match_widget_crate_path};
+
+pub struct MatchWidget {
+    /// The path `druid_enums` is available at, from `#[matcher(crate = "...")]`.
+    pub crate_path: Option<Path>,
+    pub enum_path: Path,
+    pub arms: Vec<MatchArm>,
+}
+
+pub struct MatchArm {
+    pub pattern: ArmPattern,
+    pub widget: Expr,
+}
+
+pub enum ArmPattern {
+    /// `Variant => ..`, the widget is a `Widget<()>`.
+    Unit(Ident),
+    /// `Variant(_) => ..` or `Variant(..) => ..`, the widget is a `Widget` of the field type.
+    Tuple(Ident),
+    /// `_ => ..`, the widget is a `Widget` of the whole enum.
+    Wildcard,
+}
+
+impl Parse for MatchWidget {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let crate_path = match_widget_crate_path(input.call(Attribute::parse_outer)?)?;
+        let enum_path = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let arms: Punctuated<MatchArm, Token![,]> = Punctuated::parse_terminated(input)?;
+        let arms: Vec<MatchArm> = arms.into_iter().collect();
+        if arms.is_empty() {
+            return Err(input.error("expected at least one arm"));
+        }
+        let wildcards = arms
+            .iter()
+            .filter(|arm| matches!(arm.pattern, ArmPattern::Wildcard))
+            .count();
+        let last_is_wildcard = matches!(arms.last().unwrap().pattern, ArmPattern::Wildcard);
+        if wildcards > 1 || (wildcards == 1 && !last_is_wildcard) {
+            return Err(input.error("the `_` arm must come last and only once"));
+        }
+        Ok(MatchWidget {
+            crate_path,
+            enum_path,
+            arms,
+        })
+    }
+}
+
+impl Parse for MatchArm {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let pattern = if input.peek(Token![_]) {
+            input.parse::<Token![_]>()?;
+            ArmPattern::Wildcard
+        } else {
+            let variant: Ident = input.parse()?;
+            if input.peek(syn::token::Paren) {
+                let content;
+                parenthesized!(content in input);
+                // The field is the data of the widget, so a name for it would never be used.
+                if content.peek(Token![_]) {
+                    content.parse::<Token![_]>()?;
+                } else if content.peek(Token![..]) {
+                    content.parse::<Token![..]>()?;
+                } else {
+                    return Err(content.error(
+                        "expected `_` or `..`, the field is the data of the widget \
+                         and can't be bound to a name",
+                    ));
+                }
+                if !content.is_empty() {
+                    return Err(content.error("only a single field can be matched"));
+                }
+                ArmPattern::Tuple(variant)
+            } else {
+                ArmPattern::Unit(variant)
+            }
+        };
+        input.parse::<Token![=>]>()?;
+        let widget = input.parse()?;
+        Ok(MatchArm { pattern, widget })
+    }
+}
+
+pub fn expand(input: MatchWidget) -> TokenStream {
+    let enum_ty = &input.enum_path;
+    let enum_pat = expr_style(enum_ty);
+    let druid_enums = input
+        .crate_path
+        .clone()
+        .unwrap_or_else(|| parse_quote!(::druid_enums));
+
+    let arms = input.arms.iter().map(|arm| {
+        let widget = &arm.widget;
+        match &arm.pattern {
+            ArmPattern::Unit(variant) => quote! {
+                .arm(
+                    |data: &#enum_ty| ::std::matches!(data, #enum_pat::#variant),
+                    #druid_enums::__private::MatchUnitArm::new(#widget),
+                )
+            },
+            ArmPattern::Tuple(variant) => quote! {
+                .arm(
+                    |data: &#enum_ty| ::std::matches!(data, #enum_pat::#variant(..)),
+                    #druid_enums::__private::MatchArm::new(
+                        |data: &#enum_ty| match data { #enum_pat::#variant(p0) => Some(p0), _ => None },
+                        |data: &mut #enum_ty| match data { #enum_pat::#variant(p0) => Some(p0), _ => None },
+                        #widget,
+                    ),
+                )
+            },
+            ArmPattern::Wildcard => quote! {
+                .arm(|_: &#enum_ty| true, #widget)
+            },
+        }
+    });
+
+    // Without a `_` arm, this lets the compiler check that every variant is covered.
+    let exhaustive_check = match input.arms.last().map(|arm| &arm.pattern) {
+        Some(ArmPattern::Wildcard) => quote!(),
+        _ => {
+            let patterns = input.arms.iter().map(|arm| match &arm.pattern {
+                ArmPattern::Unit(variant) => quote!(#enum_pat::#variant),
+                ArmPattern::Tuple(variant) => quote!(#enum_pat::#variant(..)),
+                ArmPattern::Wildcard => unreachable!(),
+            });
+            quote! {
+                let _ = |data: &#enum_ty| match data { #(#patterns => (),)* };
+            }
+        }
+    };
+
+    quote! {{
+        #exhaustive_check
+        #druid_enums::__private::MatchWidget::<#enum_ty>::new()
+            #(#arms)*
+    }}
+}
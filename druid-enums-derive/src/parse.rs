@@ -0,0 +1,841 @@
+use heck::SnakeCase;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::collections::HashSet;
+use syn::{
+    ext::IdentExt,
+    parenthesized,
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    Attribute, Data, DataStruct, DataUnion, DeriveInput, Error, Fields, GenericParam, Generics,
+    Ident, LitInt, LitStr, Member, Path, PathArguments, Result, Token, Type, Visibility,
+};
+
+pub struct MatcherDerive {
+    pub enum_name: Ident,
+    pub visibility: Visibility,
+    pub matcher_name: Option<Ident>,
+    pub kind_name: Option<Ident>,
+    pub generics: Generics,
+    /// The enum the matcher is for, when the derive is on a mirror of a foreign enum.
+    pub remote: Option<Path>,
+    /// The path `druid` is available at, for when it is re-exported by another crate.
+    pub druid_path: Option<Path>,
+    /// The path `druid_enums` is available at, for when it is re-exported by another crate.
+    pub crate_path: Option<Path>,
+    /// Whether the matcher is generic over the widget of each variant, instead of boxing them.
+    pub static_dispatch: bool,
+    pub missing: Missing,
+    /// Whether the variants are the ordered steps of a wizard.
+    pub wizard: bool,
+    /// Fields every variant has, reachable through a generated lens.
+    pub common: Vec<Common>,
+    /// Data passed along with the enum, making the matcher a widget for `(Context, Enum)`.
+    pub context: Option<Type>,
+    pub variants: Vec<MatcherVariant>,
+}
+
+impl MatcherDerive {
+    pub fn resolve_matcher_name(&self) -> Ident {
+        self.matcher_name.as_ref().cloned().unwrap_or_else(|| {
+            // TODO at least think about how this name is constructed, make it a concious choice.
+            Ident::new(&format!("{}Matcher", self.enum_name), self.enum_name.span())
+        })
+    }
+
+    pub fn resolve_kind_name(&self) -> Ident {
+        self.kind_name.as_ref().cloned().unwrap_or_else(|| {
+            Ident::new(&format!("{}Kind", self.enum_name), self.enum_name.span())
+        })
+    }
+
+    pub fn resolve_druid_path(&self) -> Path {
+        self.druid_path
+            .as_ref()
+            .cloned()
+            .unwrap_or_else(|| parse_quote!(::druid))
+    }
+
+    pub fn resolve_crate_path(&self) -> Path {
+        self.crate_path
+            .as_ref()
+            .cloned()
+            .unwrap_or_else(|| parse_quote!(::druid_enums))
+    }
+
+    /// The generics of the enum, with every type parameter bound by `Data`.
+    pub fn resolve_data_generics(&self) -> Generics {
+        let druid = self.resolve_druid_path();
+        let mut generics = self.generics.clone();
+        for param in generics.params.iter_mut() {
+            if let GenericParam::Type(param) = param {
+                param.bounds.push(parse_quote!(#druid::Data));
+            }
+        }
+        generics
+    }
+
+    /// The path of the matched enum, usable as a type.
+    pub fn resolve_enum_path(&self) -> Path {
+        self.remote.as_ref().cloned().unwrap_or_else(|| {
+            let enum_name = &self.enum_name;
+            let (_, ty_generics, _) = self.generics.split_for_impl();
+            parse_quote!(#enum_name #ty_generics)
+        })
+    }
+
+    /// The first variant of each group and all other variants that aren't skipped,
+    /// i.e. one variant for each widget of the matcher.
+    pub fn slot_variants(&self) -> Vec<&MatcherVariant> {
+        let mut slots = HashSet::new();
+        self.variants
+            .iter()
+            .filter(|variant| !variant.skip)
+            .filter(|variant| slots.insert(variant.resolve_slot_name().to_string()))
+            .collect()
+    }
+
+    /// The data of the matcher, the enum or the context along with the enum.
+    pub fn resolve_data_type(&self) -> Type {
+        let enum_path = self.resolve_enum_path();
+        match &self.context {
+            Some(context) => parse_quote!((#context, #enum_path)),
+            None => parse_quote!(#enum_path),
+        }
+    }
+}
+
+impl Parse for MatcherDerive {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let input: DeriveInput = input.parse()?;
+        let enum_name = input.ident;
+        let visibility = input.vis;
+        let generics = input.generics;
+        let data = match input.data {
+            Data::Enum(data) => Ok(data),
+            Data::Struct(DataStruct { struct_token, .. }) => enum_error(struct_token.span),
+            Data::Union(DataUnion { union_token, .. }) => enum_error(union_token.span),
+        }?;
+        let mut matcher_name = None;
+        let mut kind_name = None;
+        let mut remote = None;
+        let mut druid_path = None;
+        let mut crate_path = None;
+        let mut static_dispatch = false;
+        let mut missing = (Missing::Warn, None);
+        let mut wizard = false;
+        let mut common = Vec::new();
+        let mut context = None;
+        for attr in process_attrs(input.attrs) {
+            match attr? {
+                MatcherAttr::BuilderName(_, span)
+                | MatcherAttr::Title(_, span)
+                | MatcherAttr::Init(_, span)
+                | MatcherAttr::Nested(span)
+                | MatcherAttr::Validate(_, span)
+                | MatcherAttr::Full(span)
+                | MatcherAttr::Lens(_, _, span)
+                | MatcherAttr::Field(_, span)
+                | MatcherAttr::Group(_, span)
+                | MatcherAttr::Skip(span)
+                | MatcherAttr::Deref(span) => {
+                    return Err(Error::new(span, "attribute not valid on enum"))
+                }
+                MatcherAttr::MatcherName(name, _) => matcher_name = Some(name),
+                MatcherAttr::KindName(name, _) => kind_name = Some(name),
+                MatcherAttr::Remote(path, _) => remote = Some(path),
+                MatcherAttr::Druid(path, _) => druid_path = Some(path),
+                MatcherAttr::Crate(path, _) => crate_path = Some(path),
+                MatcherAttr::StaticDispatch(_) => static_dispatch = true,
+                MatcherAttr::Missing(value, span) => missing = (value, Some(span)),
+                MatcherAttr::Wizard(_) => wizard = true,
+                MatcherAttr::Common(field, _) => common.push(field),
+                MatcherAttr::Context(ty, _) => context = Some(ty),
+            }
+        }
+        if let (true, (Missing::Placeholder, Some(span))) = (static_dispatch, &missing) {
+            return Err(Error::new(
+                *span,
+                "`missing = \"placeholder\"` can't be combined with `static_dispatch`",
+            ));
+        }
+        let missing = missing.0;
+        let mut variants = Vec::new();
+        for variant in data.variants {
+            let variant_name = variant.ident;
+            let name_span = variant_name.span();
+            if let Fields::Named(_) = variant.fields {
+                return variant_error(name_span);
+            }
+            let attrs = VariantAttrs::parse(variant.attrs)?;
+            for (attr, span) in [
+                ("init", attrs.init_span),
+                ("nested", attrs.nested),
+                ("validate", attrs.validate_span),
+            ] {
+                if let (Some(span), false) = (span, variant.fields.len() == 1) {
+                    return Err(Error::new(
+                        span,
+                        format!("`{}` is only valid on variants with a single field", attr),
+                    ));
+                }
+            }
+            if let (Some(span), Some(_)) = (attrs.full, attrs.nested) {
+                return Err(Error::new(span, "`full` can't be combined with `nested`"));
+            }
+            if let (Some((_, span)), true) = (
+                &attrs.group,
+                attrs.nested.is_some() || attrs.builder_name.is_some(),
+            ) {
+                return Err(Error::new(
+                    *span,
+                    "`group` can't be combined with `nested` or `builder_name`",
+                ));
+            }
+            let builds_widget = attrs.builder_name.is_some()
+                || attrs.group.is_some()
+                || attrs.nested.is_some()
+                || attrs.full.is_some()
+                || attrs.lens.is_some()
+                || attrs.field.is_some()
+                || attrs.deref.is_some();
+            if let (Some(span), true) = (attrs.skip, builds_widget) {
+                return Err(Error::new(
+                    span,
+                    "`skip` can't be combined with attributes about the widget of the variant",
+                ));
+            }
+            for (attr, span) in [("lens", attrs.lens_span), ("field", attrs.field_span)] {
+                if let (Some(span), true) = (span, attrs.full.is_some() || attrs.nested.is_some()) {
+                    return Err(Error::new(
+                        span,
+                        format!("`{}` can't be combined with `full` or `nested`", attr),
+                    ));
+                }
+            }
+            if let (Some(span), Some(field)) = (attrs.field_span, attrs.field) {
+                if field >= variant.fields.len() {
+                    return Err(Error::new(
+                        span,
+                        format!("the variant has no field {}", field),
+                    ));
+                }
+            }
+            if let (Some(span), None, false) =
+                (attrs.lens_span, attrs.field, variant.fields.len() == 1)
+            {
+                return Err(Error::new(
+                    span,
+                    "`lens` needs a variant with a single field, or a `field` to apply it to",
+                ));
+            }
+            if let (Some(span), true) = (
+                attrs.deref,
+                attrs.full.is_some() || attrs.nested.is_some() || attrs.lens.is_some(),
+            ) {
+                return Err(Error::new(
+                    span,
+                    "`deref` can't be combined with `full`, `nested` or `lens`",
+                ));
+            }
+            if let (Some(span), None, false) = (attrs.deref, attrs.field, variant.fields.len() == 1)
+            {
+                return Err(Error::new(
+                    span,
+                    "`deref` needs a variant with a single field, or a `field` to apply it to",
+                ));
+            }
+            for (attr, span) in [("nested", attrs.nested), ("deref", attrs.deref)] {
+                if let (Some(span), Some(_)) = (span, &context) {
+                    return Err(Error::new(
+                        span,
+                        format!("`{}` can't be used in enums with a `context`", attr),
+                    ));
+                }
+            }
+            if let (Some(span), false) = (attrs.validate_span, wizard) {
+                return Err(Error::new(
+                    span,
+                    "`validate` is only valid in enums with `#[matcher(wizard)]`",
+                ));
+            }
+            let variant = MatcherVariant {
+                builder_name: attrs.builder_name,
+                title: attrs.title,
+                init: attrs.init,
+                nested: attrs.nested.is_some(),
+                validate: attrs.validate,
+                full: attrs.full.is_some(),
+                lens: attrs.lens,
+                field: attrs.field,
+                group: attrs.group.map(|(group, _)| group),
+                skip: attrs.skip.is_some(),
+                deref: attrs.deref.is_some(),
+                name: variant_name,
+                fields: variant.fields,
+            };
+            if let (Some(field), true) = (common.first(), variant.fields.is_empty()) {
+                return Err(Error::new(
+                    name_span,
+                    format!(
+                        "`common` fields like `{}` need every variant to have fields",
+                        field.name
+                    ),
+                ));
+            }
+            if wizard && !variant.is_switchable() {
+                return Err(Error::new(
+                    name_span,
+                    "steps of a wizard need an `init`, unless they are unit variants",
+                ));
+            }
+            variants.push(variant);
+        }
+        check_groups(&variants)?;
+        Ok(MatcherDerive {
+            enum_name,
+            visibility,
+            matcher_name,
+            kind_name,
+            generics,
+            remote,
+            druid_path,
+            crate_path,
+            static_dispatch,
+            missing,
+            wizard,
+            common,
+            context,
+            variants,
+        })
+    }
+}
+
+/// Checks that the variants of each group can share a widget, and that no group takes
+/// the builder of another variant.
+fn check_groups(variants: &[MatcherVariant]) -> Result<()> {
+    for variant in variants {
+        let group = match &variant.group {
+            Some(group) => group,
+            None => continue,
+        };
+        let builder = variants
+            .iter()
+            .filter(|other| other.group.is_none() && !other.skip)
+            .find(|other| other.resolve_builder_name() == *group);
+        if let Some(other) = builder {
+            return Err(Error::new(
+                group.span(),
+                format!(
+                    "group `{}` has the same name as the builder of `{}`",
+                    group, other.name
+                ),
+            ));
+        }
+        let first = variants
+            .iter()
+            .find(|other| other.group.as_ref() == Some(group))
+            .unwrap();
+        let data_type = |variant: &MatcherVariant| match variant.full {
+            true => None,
+            false => Some(variant.fields_type().to_string()),
+        };
+        if data_type(first) != data_type(variant) {
+            return Err(Error::new(
+                group.span(),
+                format!(
+                    "`{}` needs a widget for other data than `{}`, so they can't share group `{}`",
+                    variant.name, first.name, group
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A field every variant has, from `#[matcher(common(name: Type = .path.to.field))]`.
+pub struct Common {
+    pub name: Ident,
+    pub ty: Type,
+    /// The field accesses leading to the field from the data of a variant, e.g. `.user`.
+    pub path: TokenStream,
+}
+
+impl Parse for Common {
+    fn parse(s: ParseStream) -> Result<Self> {
+        let name = s.parse()?;
+        s.parse::<Token![:]>()?;
+        let ty = s.parse()?;
+        s.parse::<Token![=]>()?;
+        let mut path = TokenStream::new();
+        loop {
+            s.parse::<Token![.]>()?;
+            let member: Member = s.parse()?;
+            path.extend(quote!(.#member));
+            if !s.peek(Token![.]) {
+                break;
+            }
+        }
+        Ok(Common { name, ty, path })
+    }
+}
+
+/// What happens to variants without a widget, once the matcher is added to the widget tree.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Missing {
+    /// Log a warning.
+    Warn,
+    /// Panic.
+    Panic,
+    /// Show a placeholder naming the variant.
+    Placeholder,
+    /// Do nothing.
+    Silent,
+}
+
+fn enum_error<T>(span: Span) -> Result<T> {
+    Err(Error::new(span, "only `enum`s can implement `Matcher`"))
+}
+
+fn variant_error<T>(span: Span) -> Result<T> {
+    Err(Error::new(
+        span,
+        "only unit and tuple variants are supported",
+    ))
+}
+
+pub struct MatcherVariant {
+    pub builder_name: Option<Ident>,
+    /// The window title while the variant is shown.
+    pub title: Option<LitStr>,
+    /// The function creating the field of the variant, when switching to it.
+    pub init: Option<Path>,
+    /// Whether the field is an enum deriving `Matcher` too, whose matcher is built in place.
+    pub nested: bool,
+    /// The function deciding whether a wizard may advance past the step.
+    pub validate: Option<Path>,
+    /// Whether the widget of the variant is a widget for the whole enum.
+    pub full: bool,
+    /// The lens from the data of the variant to the data of its widget, and the type it leads to.
+    pub lens: Option<(Path, Type)>,
+    /// The index of the only field the widget of the variant gets.
+    pub field: Option<usize>,
+    /// The group of variants sharing a builder and widget with this one.
+    pub group: Option<Ident>,
+    /// Whether the variant has no builder and widget of its own, showing the default instead.
+    pub skip: bool,
+    /// Whether the field is an `Rc` or `Arc` whose content is the data of the widget.
+    pub deref: bool,
+    pub name: Ident,
+    pub fields: Fields,
+}
+
+impl MatcherVariant {
+    pub fn resolve_builder_name(&self) -> Ident {
+        self.builder_name
+            .as_ref()
+            .cloned()
+            .unwrap_or_else(|| snakify(&self.name))
+    }
+
+    /// The name of the field holding the widget of the variant, shared by the variants of a group.
+    pub fn resolve_slot_name(&self) -> Ident {
+        self.group
+            .as_ref()
+            .cloned()
+            .unwrap_or_else(|| self.resolve_builder_name())
+    }
+
+    /// The data of the widget of the variant, unless it's `full`, without the context.
+    pub fn fields_type(&self) -> TokenStream {
+        let fields_ty = match (&self.fields, &self.lens, self.field) {
+            (_, Some((_, ty)), _) => quote!(#ty),
+            (Fields::Unnamed(fields), None, Some(field)) => {
+                let ty = &fields.unnamed[field].ty;
+                quote!(#ty)
+            }
+            (Fields::Unit, ..) => quote!(()),
+            (Fields::Unnamed(fields), ..) if fields.unnamed.is_empty() => quote!(()),
+            (Fields::Unnamed(fields), ..) => {
+                let types = fields.unnamed.iter().map(|f| &f.ty);
+                quote!((#(#types),*))
+            }
+            (Fields::Named(_), ..) => unreachable!(),
+        };
+        match self.deref {
+            true => quote!(<#fields_ty as ::std::ops::Deref>::Target),
+            false => fields_ty,
+        }
+    }
+
+    /// Whether the data of the variant can be created when switching to it.
+    pub fn is_switchable(&self) -> bool {
+        self.fields.is_empty() || self.init.is_some()
+    }
+}
+
+#[derive(Default)]
+struct VariantAttrs {
+    /// The name of the function call to build the corresponding widget.
+    builder_name: Option<Ident>,
+    title: Option<LitStr>,
+    init: Option<Path>,
+    init_span: Option<Span>,
+    nested: Option<Span>,
+    validate: Option<Path>,
+    validate_span: Option<Span>,
+    full: Option<Span>,
+    lens: Option<(Path, Type)>,
+    lens_span: Option<Span>,
+    field: Option<usize>,
+    field_span: Option<Span>,
+    group: Option<(Ident, Span)>,
+    skip: Option<Span>,
+    deref: Option<Span>,
+}
+
+impl VariantAttrs {
+    fn parse(input: Vec<Attribute>) -> Result<Self> {
+        let mut matcher_attrs = VariantAttrs::default();
+        for attr in process_attrs(input) {
+            match attr? {
+                MatcherAttr::BuilderName(builder_name, _) => {
+                    matcher_attrs.builder_name = Some(builder_name)
+                }
+                MatcherAttr::Title(title, _) => matcher_attrs.title = Some(title),
+                MatcherAttr::Init(init, span) => {
+                    matcher_attrs.init = Some(init);
+                    matcher_attrs.init_span = Some(span);
+                }
+                MatcherAttr::Nested(span) => matcher_attrs.nested = Some(span),
+                MatcherAttr::Validate(validate, span) => {
+                    matcher_attrs.validate = Some(validate);
+                    matcher_attrs.validate_span = Some(span);
+                }
+                MatcherAttr::Full(span) => matcher_attrs.full = Some(span),
+                MatcherAttr::Lens(lens, ty, span) => {
+                    matcher_attrs.lens = Some((lens, ty));
+                    matcher_attrs.lens_span = Some(span);
+                }
+                MatcherAttr::Field(field, span) => {
+                    matcher_attrs.field = Some(field);
+                    matcher_attrs.field_span = Some(span);
+                }
+                MatcherAttr::Group(group, span) => matcher_attrs.group = Some((group, span)),
+                MatcherAttr::Skip(span) => matcher_attrs.skip = Some(span),
+                MatcherAttr::Deref(span) => matcher_attrs.deref = Some(span),
+                MatcherAttr::MatcherName(_, span)
+                | MatcherAttr::KindName(_, span)
+                | MatcherAttr::Remote(_, span)
+                | MatcherAttr::Druid(_, span)
+                | MatcherAttr::Crate(_, span)
+                | MatcherAttr::StaticDispatch(span)
+                | MatcherAttr::Missing(_, span)
+                | MatcherAttr::Wizard(span)
+                | MatcherAttr::Common(_, span)
+                | MatcherAttr::Context(_, span) => {
+                    return Err(Error::new(span, "attribute not valid on variants"))
+                }
+            }
+        }
+        Ok(matcher_attrs)
+    }
+}
+
+/// Parses the attributes of a `WidgetEnum`, returning the `druid` path if there is one.
+pub fn widget_enum_druid_path(input: Vec<Attribute>) -> Result<Option<Path>> {
+    let mut druid_path = None;
+    for attr in process_attrs(input) {
+        match attr? {
+            MatcherAttr::Druid(path, _) => druid_path = Some(path),
+            MatcherAttr::MatcherName(_, span)
+            | MatcherAttr::BuilderName(_, span)
+            | MatcherAttr::KindName(_, span)
+            | MatcherAttr::Remote(_, span)
+            | MatcherAttr::Crate(_, span)
+            | MatcherAttr::StaticDispatch(span)
+            | MatcherAttr::Missing(_, span)
+            | MatcherAttr::Title(_, span)
+            | MatcherAttr::Init(_, span)
+            | MatcherAttr::Nested(span)
+            | MatcherAttr::Wizard(span)
+            | MatcherAttr::Validate(_, span)
+            | MatcherAttr::Common(_, span)
+            | MatcherAttr::Full(span)
+            | MatcherAttr::Context(_, span)
+            | MatcherAttr::Lens(_, _, span)
+            | MatcherAttr::Field(_, span)
+            | MatcherAttr::Group(_, span)
+            | MatcherAttr::Skip(span)
+            | MatcherAttr::Deref(span) => {
+                return Err(Error::new(span, "attribute not valid for `WidgetEnum`"))
+            }
+        }
+    }
+    Ok(druid_path)
+}
+
+/// Parses the attributes of a `match_widget!`, returning the `crate` path if there is one.
+pub fn match_widget_crate_path(input: Vec<Attribute>) -> Result<Option<Path>> {
+    let mut crate_path = None;
+    for attr in process_attrs(input) {
+        match attr? {
+            MatcherAttr::Crate(path, _) => crate_path = Some(path),
+            MatcherAttr::MatcherName(_, span)
+            | MatcherAttr::BuilderName(_, span)
+            | MatcherAttr::KindName(_, span)
+            | MatcherAttr::Remote(_, span)
+            | MatcherAttr::Druid(_, span)
+            | MatcherAttr::StaticDispatch(span)
+            | MatcherAttr::Missing(_, span)
+            | MatcherAttr::Title(_, span)
+            | MatcherAttr::Init(_, span)
+            | MatcherAttr::Nested(span)
+            | MatcherAttr::Wizard(span)
+            | MatcherAttr::Validate(_, span)
+            | MatcherAttr::Common(_, span)
+            | MatcherAttr::Full(span)
+            | MatcherAttr::Context(_, span)
+            | MatcherAttr::Lens(_, _, span)
+            | MatcherAttr::Field(_, span)
+            | MatcherAttr::Group(_, span)
+            | MatcherAttr::Skip(span)
+            | MatcherAttr::Deref(span) => {
+                return Err(Error::new(span, "attribute not valid for `match_widget!`"))
+            }
+        }
+    }
+    Ok(crate_path)
+}
+
+// spans are for error reporting.
+enum MatcherAttr {
+    MatcherName(Ident, Span),
+    BuilderName(Ident, Span),
+    KindName(Ident, Span),
+    Remote(Path, Span),
+    Druid(Path, Span),
+    Crate(Path, Span),
+    StaticDispatch(Span),
+    Missing(Missing, Span),
+    Title(LitStr, Span),
+    Init(Path, Span),
+    Nested(Span),
+    Wizard(Span),
+    Validate(Path, Span),
+    Common(Common, Span),
+    Full(Span),
+    Context(Type, Span),
+    Lens(Path, Type, Span),
+    Field(usize, Span),
+    Group(Ident, Span),
+    Skip(Span),
+    Deref(Span),
+}
+
+impl Parse for MatcherAttr {
+    fn parse(s: ParseStream) -> Result<Self> {
+        // `crate` is a keyword, so it isn't parsed as an `Ident` otherwise.
+        let attr_name = Ident::parse_any(s)?;
+        let name_span = attr_name.span();
+        match attr_name.to_string().as_str() {
+            "builder_name" => {
+                s.parse::<Token![=]>()?;
+                s.parse()
+                    .map(|builder_name| MatcherAttr::BuilderName(builder_name, name_span))
+            }
+            "matcher_name" => {
+                s.parse::<Token![=]>()?;
+                s.parse()
+                    .map(|matcher_name| MatcherAttr::MatcherName(matcher_name, name_span))
+            }
+            "kind_name" => {
+                s.parse::<Token![=]>()?;
+                s.parse()
+                    .map(|kind_name| MatcherAttr::KindName(kind_name, name_span))
+            }
+            "remote" => {
+                s.parse::<Token![=]>()?;
+                s.parse::<LitStr>()
+                    .and_then(|remote| remote.parse())
+                    .map(|remote| MatcherAttr::Remote(remote, name_span))
+            }
+            "druid" => {
+                s.parse::<Token![=]>()?;
+                s.parse::<LitStr>()
+                    .and_then(|druid| druid.parse())
+                    .map(|druid| MatcherAttr::Druid(druid, name_span))
+            }
+            "crate" => {
+                s.parse::<Token![=]>()?;
+                s.parse::<LitStr>()
+                    .and_then(|krate| krate.parse())
+                    .map(|krate| MatcherAttr::Crate(krate, name_span))
+            }
+            "static_dispatch" => Ok(MatcherAttr::StaticDispatch(name_span)),
+            "title" => {
+                s.parse::<Token![=]>()?;
+                s.parse().map(|title| MatcherAttr::Title(title, name_span))
+            }
+            "init" => {
+                s.parse::<Token![=]>()?;
+                s.parse().map(|init| MatcherAttr::Init(init, name_span))
+            }
+            "nested" => Ok(MatcherAttr::Nested(name_span)),
+            "wizard" => Ok(MatcherAttr::Wizard(name_span)),
+            "full" => Ok(MatcherAttr::Full(name_span)),
+            "lens" => {
+                s.parse::<Token![=]>()?;
+                let lens = s.parse()?;
+                s.parse::<Token![:]>()?;
+                s.parse().map(|ty| MatcherAttr::Lens(lens, ty, name_span))
+            }
+            "field" => {
+                s.parse::<Token![=]>()?;
+                s.parse::<LitInt>()?
+                    .base10_parse()
+                    .map(|field| MatcherAttr::Field(field, name_span))
+            }
+            "skip" => Ok(MatcherAttr::Skip(name_span)),
+            "deref" => Ok(MatcherAttr::Deref(name_span)),
+            "group" => {
+                s.parse::<Token![=]>()?;
+                s.parse().map(|group| MatcherAttr::Group(group, name_span))
+            }
+            "context" => {
+                s.parse::<Token![=]>()?;
+                s.parse()
+                    .map(|context| MatcherAttr::Context(context, name_span))
+            }
+            "common" => {
+                let content;
+                parenthesized!(content in s);
+                content
+                    .parse()
+                    .map(|common| MatcherAttr::Common(common, name_span))
+            }
+            "validate" => {
+                s.parse::<Token![=]>()?;
+                s.parse()
+                    .map(|validate| MatcherAttr::Validate(validate, name_span))
+            }
+            "missing" => {
+                s.parse::<Token![=]>()?;
+                let missing = s.parse::<LitStr>()?;
+                let expected =
+                    "expected `\"warn\"`, `\"panic\"`, `\"placeholder\"` or `\"silent\"`";
+                let value = match missing.value().as_str() {
+                    "warn" => Missing::Warn,
+                    "panic" => Missing::Panic,
+                    "placeholder" => Missing::Placeholder,
+                    "silent" => Missing::Silent,
+                    _ => return Err(Error::new(missing.span(), expected)),
+                };
+                Ok(MatcherAttr::Missing(value, name_span))
+            }
+            other => Err(Error::new(
+                name_span,
+                format!(
+                    "expected `builder_name`, `matcher_name`, `kind_name`, `remote`, `druid`, \
+                     `crate`, `static_dispatch`, `missing`, `title`, `init`, `nested`, `wizard`, \
+                     `validate`, `common`, `full`, `context`, `lens`, `field`, `group`, `skip` or \
+                     `deref`, found `{}`",
+                    other
+                ),
+            )),
+        }
+    }
+}
+
+/// Go through a set of `Attribute`s and process them into an iterator of parsed attributes
+fn process_attrs(input: Vec<Attribute>) -> impl Iterator<Item = Result<MatcherAttr>> {
+    ProcessAttrs {
+        attrs: input.into_iter(),
+        part: None,
+    }
+}
+
+struct ProcessAttrs {
+    attrs: std::vec::IntoIter<Attribute>,
+    part: Option<syn::punctuated::IntoIter<MatcherAttr>>,
+}
+
+impl ProcessAttrs {
+    /// Get the next part, or `None` if there aren't any.
+    fn next_part(&mut self) -> Option<MatcherAttr> {
+        match &mut self.part {
+            Some(iter) => iter.next(),
+            None => None,
+        }
+    }
+
+    /// Find the next `matches` attr and load it into `part`
+    fn load_parts(&mut self) -> Result<()> {
+        assert!(self.part.as_mut().and_then(|iter| iter.next()).is_none());
+        loop {
+            let attr = match self.attrs.next() {
+                Some(a) => a,
+                None => return Ok(()),
+            };
+            if !matches_path(&attr.path) {
+                continue;
+            }
+            let match_attrs: Punctuated<_, Token![,]> =
+                attr.parse_args_with(Punctuated::parse_terminated)?;
+            self.part = Some(match_attrs.into_iter());
+            return Ok(());
+        }
+    }
+}
+
+impl Iterator for ProcessAttrs {
+    type Item = Result<MatcherAttr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(matcher_attr) = self.next_part() {
+            return Some(Ok(matcher_attr));
+        }
+        if let Err(e) = self.load_parts() {
+            return Some(Err(e));
+        }
+        self.next_part().map(Ok)
+    }
+}
+
+/// True if the path is exactly `matches`.
+fn matches_path(p: &Path) -> bool {
+    if p.leading_colon.is_some() {
+        return false;
+    }
+    let segment = match iter_get_one(p.segments.iter()) {
+        Some(s) => s,
+        None => return false,
+    };
+    segment.ident == "matcher" && segment.arguments.is_empty()
+}
+
+fn iter_get_one<T, I: IntoIterator<Item = T>>(iter: I) -> Option<T> {
+    let mut iter = iter.into_iter();
+    let result = iter.next()?;
+    if iter.next().is_some() {
+        return None;
+    }
+    Some(result)
+}
+
+/// Turns `Option<T>` into `Option::<T>`, so the path can be used in patterns.
+pub fn expr_style(path: &Path) -> Path {
+    let mut path = path.clone();
+    for segment in path.segments.iter_mut() {
+        if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+            args.colon2_token = Some(Default::default());
+        }
+    }
+    path
+}
+
+fn snakify(input: &Ident) -> Ident {
+    let new_name = input.to_string().to_snake_case();
+    Ident::new(&new_name, input.span())
+}
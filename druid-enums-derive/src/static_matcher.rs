@@ -0,0 +1,252 @@
+//! The matcher generated with `#[matcher(static_dispatch)]`.
+//!
+//! Instead of boxing the widget of each variant, the matcher has one type parameter per variant.
+//! Every builder returns a matcher with the new widget type, variants without a widget are
+//! `druid_enums::Unset`.
+
+use crate::parse::{MatcherDerive, MatcherVariant};
+use crate::{
+    active_enum, any_of, expr_style, introspection_fns, matcher_enum_impl, nested_builder,
+    on_missing_widget, presentation_fns, type_of, visit_variant, widget_impl,
+};
+use heck::CamelCase;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_quote, GenericParam, Ident};
+
+pub fn expand(input: &MatcherDerive) -> TokenStream {
+    let druid_enums = input.resolve_crate_path();
+    let visibility = &input.visibility;
+    let enum_name = &input.enum_name;
+    let enum_path = input.resolve_enum_path();
+    let data_ty = input.resolve_data_type();
+    let matcher_name = input.resolve_matcher_name();
+    let druid = input.resolve_druid_path();
+    let kind_name = input.resolve_kind_name();
+    let data_generics = input.resolve_data_generics();
+    let (data_impl_generics, data_ty_generics, data_where_clause) = data_generics.split_for_impl();
+
+    // One widget for each variant, or group of variants.
+    let slots = input.slot_variants();
+    let widget_params: Vec<Ident> = slots.iter().map(|variant| widget_param(variant)).collect();
+    let slot_names: Vec<Ident> = slots
+        .iter()
+        .map(|variant| variant.resolve_slot_name())
+        .collect();
+
+    // The generics of the enum followed by one widget parameter for each slot.
+    let mut generics = data_generics.clone();
+    for (param, variant) in widget_params.iter().zip(&slots) {
+        let variant_ty = type_of(input, variant);
+        generics
+            .params
+            .push(parse_quote!(#param: #druid::Widget<#variant_ty> + 'static));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // The arguments for the generics of the enum.
+    let enum_args: Vec<TokenStream> = input
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote!(#lifetime)
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+        })
+        .collect();
+    let matcher_ty = |widgets: &[TokenStream]| quote!(#matcher_name<#(#enum_args,)* #(#widgets),*>);
+
+    let unset_widgets: Vec<TokenStream> =
+        slots.iter().map(|_| quote!(#druid_enums::Unset)).collect();
+    let unset_matcher = matcher_ty(&unset_widgets);
+
+    let struct_fields = slots.iter().zip(&widget_params).map(|(variant, param)| {
+        let builder_name = variant.resolve_slot_name();
+        let variant_ty = type_of(input, variant);
+        quote!(#builder_name: #druid::WidgetPod<#variant_ty, #param>)
+    });
+
+    let struct_defaults = slots.iter().map(|variant| {
+        let builder_name = variant.resolve_slot_name();
+        quote!(#builder_name: #druid::WidgetPod::new(#druid_enums::Unset))
+    });
+
+    let builder_fns = slots.iter().enumerate().map(|(index, variant)| {
+        let builder_name = variant.resolve_slot_name();
+        let variant_ty = type_of(input, variant);
+        let widgets: Vec<TokenStream> = widget_params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| match i == index {
+                true => quote!(__W),
+                false => quote!(#param),
+            })
+            .collect();
+        let built_matcher = matcher_ty(&widgets);
+        let (build_param, widget, bound) = nested_builder(input, variant)
+            .unwrap_or_else(|| (quote!(widget: __W), quote!(widget), quote!()));
+        let fields = slot_names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| match i == index {
+                true => quote!(#name: #druid::WidgetPod::new(#widget)),
+                false => quote!(#name: self.#name),
+            });
+        quote! {
+            pub fn #builder_name<__W: #druid::Widget<#variant_ty> + 'static>(
+                self,
+                #build_param,
+            ) -> #built_matcher #bound {
+                #matcher_name {
+                    #(#fields,)*
+                    kind_: self.kind_,
+                    present_: self.present_,
+                }
+            }
+        }
+    });
+
+    let has_widget_match = input.variants.iter().map(|variant| {
+        let variant_name = &variant.name;
+        let param = widget_param(variant);
+        match variant.skip {
+            true => quote!(#kind_name::#variant_name => true),
+            false => {
+                quote!(#kind_name::#variant_name => !#druid_enums::__private::is_unset::<#param>())
+            }
+        }
+    });
+
+    let widget_added_checks = slots
+        .iter()
+        .zip(&widget_params)
+        .filter_map(|(variant, param)| {
+            let on_missing = on_missing_widget(input, variant)?;
+            Some(quote! {
+                if #druid_enums::__private::is_unset::<#param>() {
+                    #on_missing
+                }
+            })
+        });
+
+    let active_child_match = |mutable| -> Vec<TokenStream> {
+        input
+            .variants
+            .iter()
+            .map(|variant| {
+                if variant.skip {
+                    let enum_pat = expr_style(&input.resolve_enum_path());
+                    let variant_name = &variant.name;
+                    let any = any_of(variant);
+                    return quote!(#enum_pat::#variant_name #any => None);
+                }
+                let builder_name = variant.resolve_slot_name();
+                let (pattern, visit) =
+                    visit_variant(input, variant, quote!(&mut self.#builder_name), mutable);
+                quote!(#pattern => Some(#visit))
+            })
+            .collect()
+    };
+    let active_child_ref_match = active_child_match(false);
+    let active_child_mut_match = active_child_match(true);
+    let (active_enum_ref, active_enum_mut) = (active_enum(input, false), active_enum(input, true));
+
+    let introspection_fns = introspection_fns(input, None);
+    let presentation_fns = presentation_fns(input);
+
+    let widget_impl = widget_impl(input, &generics, quote!(), quote!(#(#widget_added_checks)*));
+
+    let matcher_enum_impl = matcher_enum_impl(input, unset_matcher.clone());
+
+    quote! {
+        impl #data_impl_generics #enum_name #data_ty_generics #data_where_clause {
+            pub fn matcher() -> #unset_matcher {
+                #matcher_name::new()
+            }
+        }
+
+        #visibility struct #matcher_name #impl_generics #where_clause {
+            #(#struct_fields,)*
+            kind_: Option<#kind_name>,
+            present_: #druid_enums::__private::Presentation,
+        }
+
+        impl #data_impl_generics #unset_matcher #data_where_clause {
+            pub fn new() -> Self {
+                #matcher_name {
+                    #(#struct_defaults,)*
+                    kind_: None,
+                    present_: Default::default(),
+                }
+            }
+        }
+
+        impl #impl_generics #matcher_name #ty_generics #where_clause {
+            #(#builder_fns)*
+            #introspection_fns
+            #presentation_fns
+
+            fn with_active_child<V: #druid_enums::__private::VisitChild>(
+                &mut self,
+                data: &#data_ty,
+                visitor: V,
+            ) -> Option<V::Output> {
+                match #active_enum_ref {
+                    #(#active_child_ref_match,)*
+                }
+            }
+
+            fn with_active_child_mut<V: #druid_enums::__private::VisitChildMut>(
+                &mut self,
+                data: &mut #data_ty,
+                visitor: V,
+            ) -> Option<V::Output> {
+                match #active_enum_mut {
+                    #(#active_child_mut_match,)*
+                }
+            }
+        }
+
+        impl #impl_generics #druid_enums::Matcher for #matcher_name #ty_generics #where_clause {
+            type Enum = #enum_path;
+            type Kind = #kind_name;
+
+            fn active_kind(&self) -> Option<#kind_name> {
+                self.kind_
+            }
+            fn has_widget_for(&self, kind: #kind_name) -> bool {
+                match kind {
+                    #(#has_widget_match,)*
+                }
+            }
+            fn set_widget_boxed(
+                &mut self,
+                _kind: #kind_name,
+                widget: Box<dyn ::std::any::Any>,
+            ) -> Result<(), Box<dyn ::std::any::Any>> {
+                Err(widget)
+            }
+        }
+
+        #widget_impl
+        #matcher_enum_impl
+    }
+}
+
+/// The type parameter for the widget of the variant, shared by the variants of a group.
+fn widget_param(variant: &MatcherVariant) -> Ident {
+    match &variant.group {
+        Some(group) => format_ident!("__W{}", group.to_string().to_camel_case()),
+        None => format_ident!("__W{}", variant.name),
+    }
+}
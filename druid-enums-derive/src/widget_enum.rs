@@ -0,0 +1,139 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote, Data, DataStruct, DataUnion, DeriveInput, Error, Fields, Generics, Ident, Path,
+    Result, Type,
+};
+
+use crate::parse::widget_enum_druid_path;
+
+/// An enum with a widget in each variant, all of them over the same data.
+pub struct WidgetEnum {
+    pub enum_name: Ident,
+    pub generics: Generics,
+    pub druid_path: Option<Path>,
+    /// The name and widget type of each variant.
+    pub variants: Vec<(Ident, Type)>,
+}
+
+impl Parse for WidgetEnum {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let input: DeriveInput = input.parse()?;
+        let data = match input.data {
+            Data::Enum(data) => data,
+            Data::Struct(DataStruct { struct_token, .. }) => {
+                return Err(Error::new(
+                    struct_token.span,
+                    "only `enum`s can implement `WidgetEnum`",
+                ))
+            }
+            Data::Union(DataUnion { union_token, .. }) => {
+                return Err(Error::new(
+                    union_token.span,
+                    "only `enum`s can implement `WidgetEnum`",
+                ))
+            }
+        };
+        let druid_path = widget_enum_druid_path(input.attrs)?;
+        let mut variants = Vec::new();
+        for variant in data.variants {
+            match variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    let field = fields.unnamed.into_iter().next().unwrap();
+                    variants.push((variant.ident, field.ty));
+                }
+                _ => {
+                    return Err(Error::new(
+                        variant.ident.span(),
+                        "every variant has to hold exactly one widget, like `Variant(Label<T>)`",
+                    ))
+                }
+            }
+        }
+        Ok(WidgetEnum {
+            enum_name: input.ident,
+            generics: input.generics,
+            druid_path,
+            variants,
+        })
+    }
+}
+
+pub fn expand(input: WidgetEnum) -> TokenStream {
+    let enum_name = &input.enum_name;
+    let druid = input.druid_path.unwrap_or_else(|| parse_quote!(::druid));
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    // The enum is a widget over any data all of its variants are widgets over.
+    let mut generics = input.generics.clone();
+    generics.params.push(parse_quote!(__T));
+    let where_clause = generics.make_where_clause();
+    for (_, ty) in &input.variants {
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: #druid::Widget<__T>));
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let variant_names: Vec<&Ident> = input.variants.iter().map(|(name, _)| name).collect();
+    let variant_names = &variant_names;
+
+    quote! {
+        impl #impl_generics #druid::Widget<__T> for #enum_name #ty_generics #where_clause {
+            fn event(
+                &mut self,
+                ctx: &mut #druid::EventCtx,
+                event: &#druid::Event,
+                data: &mut __T,
+                env: &#druid::Env
+            ) {
+                match self {
+                    #(#enum_name::#variant_names(widget) => widget.event(ctx, event, data, env),)*
+                }
+            }
+            fn lifecycle(
+                &mut self,
+                ctx: &mut #druid::LifeCycleCtx,
+                event: &#druid::LifeCycle,
+                data: &__T,
+                env: &#druid::Env
+            ) {
+                match self {
+                    #(#enum_name::#variant_names(widget) => widget.lifecycle(ctx, event, data, env),)*
+                }
+            }
+            fn update(&mut self,
+                ctx: &mut #druid::UpdateCtx,
+                old_data: &__T,
+                data: &__T,
+                env: &#druid::Env
+            ) {
+                match self {
+                    #(#enum_name::#variant_names(widget) => widget.update(ctx, old_data, data, env),)*
+                }
+            }
+            fn layout(
+                &mut self,
+                ctx: &mut #druid::LayoutCtx,
+                bc: &#druid::BoxConstraints,
+                data: &__T,
+                env: &#druid::Env
+            ) -> #druid::Size {
+                match self {
+                    #(#enum_name::#variant_names(widget) => widget.layout(ctx, bc, data, env),)*
+                }
+            }
+            fn paint(&mut self, ctx: &mut #druid::PaintCtx, data: &__T, env: &#druid::Env) {
+                match self {
+                    #(#enum_name::#variant_names(widget) => widget.paint(ctx, data, env),)*
+                }
+            }
+            fn id(&self) -> Option<#druid::WidgetId> {
+                match self {
+                    #(#enum_name::#variant_names(widget) => widget.id(),)*
+                }
+            }
+        }
+    }
+}
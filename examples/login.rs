@@ -1,3 +1,8 @@
+#[cfg(feature = "druid-0-7")]
+extern crate druid_0_7 as druid;
+#[cfg(feature = "druid-0-8")]
+extern crate druid_0_8 as druid;
+
 use druid::{
     widget::{Button, Controller, Flex, Label, TextBox},
     AppLauncher, Data, Env, Event, EventCtx, Lens, PlatformError, Selector, Widget, WidgetExt,
@@ -25,6 +30,7 @@ struct MainState {
     count: u32,
 }
 
+#[cfg(feature = "druid-0-7")]
 fn main() -> Result<(), PlatformError> {
     let window = WindowDesc::new(ui).title("Druid Enums");
     let state = AppState::Login(LoginState::default());
@@ -33,6 +39,15 @@ fn main() -> Result<(), PlatformError> {
         .launch(state)
 }
 
+#[cfg(feature = "druid-0-8")]
+fn main() -> Result<(), PlatformError> {
+    let window = WindowDesc::new(ui()).title("Druid Enums");
+    let state = AppState::Login(LoginState::default());
+    AppLauncher::with_window(window)
+        .log_to_console()
+        .launch(state)
+}
+
 fn ui() -> impl Widget<AppState> {
     // AppState::matcher() or
     App::new()
@@ -0,0 +1,214 @@
+//! Compile time benchmark for enums with many variants.
+//!
+//! `scripts/compile_time.sh` builds this example at two revisions, taking turns, and prints
+//! the median time of rebuilding it:
+//! ```sh
+//! scripts/compile_time.sh BEFORE [AFTER] [RUNS]
+//! ```
+//!
+//! Medians of 25 debug builds without incremental compilation, before and after matchers
+//! dispatched through one visitor. They were measured with a minimal stand-in for druid,
+//! as druid's dependencies couldn't be fetched on that machine, so druid's own generic code
+//! isn't part of the times:
+//!
+//! | revision                     | expanded lines | `cargo check` | `cargo build` |
+//! |------------------------------|---------------:|--------------:|--------------:|
+//! | six matches per matcher      |           7680 |        649 ms |       1111 ms |
+//! | one visitor for each matcher |           5822 |        560 ms |        982 ms |
+//!
+//! The later revision also generates routes, menus and introspection, whose per-variant code
+//! is kept to tables and calls into `druid_enums` wherever possible.
+
+#[cfg(feature = "druid-0-7")]
+extern crate druid_0_7 as druid;
+#[cfg(feature = "druid-0-8")]
+extern crate druid_0_8 as druid;
+
+use druid::{widget::Label, Data, Widget};
+use druid_enums::Matcher;
+
+#[allow(dead_code)]
+#[derive(Clone, Data, Matcher)]
+enum Screens {
+    Screen0(u32),
+    Screen1(u32),
+    Screen2(u32),
+    Screen3(u32),
+    Screen4(u32),
+    Screen5(u32),
+    Screen6(u32),
+    Screen7(u32),
+    Screen8(u32),
+    Screen9(u32),
+    Screen10(u32),
+    Screen11(u32),
+    Screen12(u32),
+    Screen13(u32),
+    Screen14(u32),
+    Screen15(u32),
+    Screen16(u32),
+    Screen17(u32),
+    Screen18(u32),
+    Screen19(u32),
+    Screen20(u32),
+    Screen21(u32),
+    Screen22(u32),
+    Screen23(u32),
+    Screen24(u32),
+    Screen25(u32),
+    Screen26(u32),
+    Screen27(u32),
+    Screen28(u32),
+    Screen29(u32),
+    Screen30(u32),
+    Screen31(u32),
+    Screen32(u32),
+    Screen33(u32),
+    Screen34(u32),
+    Screen35(u32),
+    Screen36(u32),
+    Screen37(u32),
+    Screen38(u32),
+    Screen39(u32),
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Data, Matcher)]
+#[matcher(static_dispatch)]
+enum StaticScreens {
+    Screen0(u32),
+    Screen1(u32),
+    Screen2(u32),
+    Screen3(u32),
+    Screen4(u32),
+    Screen5(u32),
+    Screen6(u32),
+    Screen7(u32),
+    Screen8(u32),
+    Screen9(u32),
+    Screen10(u32),
+    Screen11(u32),
+    Screen12(u32),
+    Screen13(u32),
+    Screen14(u32),
+    Screen15(u32),
+    Screen16(u32),
+    Screen17(u32),
+    Screen18(u32),
+    Screen19(u32),
+    Screen20(u32),
+    Screen21(u32),
+    Screen22(u32),
+    Screen23(u32),
+    Screen24(u32),
+    Screen25(u32),
+    Screen26(u32),
+    Screen27(u32),
+    Screen28(u32),
+    Screen29(u32),
+    Screen30(u32),
+    Screen31(u32),
+    Screen32(u32),
+    Screen33(u32),
+    Screen34(u32),
+    Screen35(u32),
+    Screen36(u32),
+    Screen37(u32),
+    Screen38(u32),
+    Screen39(u32),
+}
+
+fn screens() -> impl Widget<Screens> {
+    Screens::matcher()
+        .screen0(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen1(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen2(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen3(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen4(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen5(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen6(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen7(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen8(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen9(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen10(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen11(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen12(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen13(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen14(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen15(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen16(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen17(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen18(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen19(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen20(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen21(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen22(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen23(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen24(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen25(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen26(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen27(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen28(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen29(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen30(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen31(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen32(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen33(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen34(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen35(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen36(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen37(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen38(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen39(Label::dynamic(|n: &u32, _| n.to_string()))
+}
+
+fn static_screens() -> impl Widget<StaticScreens> {
+    StaticScreens::matcher()
+        .screen0(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen1(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen2(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen3(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen4(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen5(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen6(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen7(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen8(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen9(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen10(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen11(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen12(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen13(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen14(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen15(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen16(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen17(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen18(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen19(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen20(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen21(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen22(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen23(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen24(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen25(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen26(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen27(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen28(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen29(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen30(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen31(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen32(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen33(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen34(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen35(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen36(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen37(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen38(Label::dynamic(|n: &u32, _| n.to_string()))
+        .screen39(Label::dynamic(|n: &u32, _| n.to_string()))
+}
+
+fn main() {
+    // As trait objects, every `Widget` method of both matchers gets compiled.
+    let screens: Box<dyn Widget<Screens>> = Box::new(screens());
+    let static_screens: Box<dyn Widget<StaticScreens>> = Box::new(static_screens());
+    let _ = (screens, static_screens);
+}
@@ -0,0 +1,62 @@
+#!/bin/sh
+# Compares the compile time of examples/many_variants.rs between two revisions.
+#
+#     scripts/compile_time.sh BEFORE [AFTER] [RUNS]
+#
+# AFTER defaults to the working tree. Both build the example of the working tree.
+# Dependencies are built first, so the times only cover the example: expanding the derives,
+# type checking and generating code for both matchers. The revisions take turns, so that
+# anything else slowing down the machine hits both of them, and incremental compilation is
+# off, as it would reuse most of the work after a `touch`. Prints the median and the fastest
+# of RUNS debug builds.
+set -e
+
+if [ -z "$1" ]; then
+    echo "usage: $0 BEFORE [AFTER] [RUNS]" >&2
+    exit 1
+fi
+before=$1
+after=${2:-}
+runs=${3:-15}
+root=$(git rev-parse --show-toplevel)
+tmp=$(mktemp -d)
+trap 'git -C "$root" worktree prune; rm -rf "$tmp"' EXIT
+export CARGO_INCREMENTAL=0
+
+checkout() {
+    git -C "$root" worktree add --detach --quiet "$tmp/$1" "$2"
+    cp "$root/examples/many_variants.rs" "$tmp/$1/examples/"
+    echo "$tmp/$1"
+}
+
+build() {
+    (cd "$1" && cargo build --quiet --example many_variants)
+}
+
+time_build() {
+    touch "$1/examples/many_variants.rs"
+    start=$(date +%s%N)
+    build "$1"
+    echo $((($(date +%s%N) - start) / 1000000)) >>"$2"
+}
+
+summary() {
+    median=$(sort -n "$1" | sed -n "$(((runs + 1) / 2))p")
+    fastest=$(sort -n "$1" | head -n 1)
+    echo "$median ms (fastest $fastest ms)"
+}
+
+before_dir=$(checkout before "$before")
+if [ -n "$after" ]; then
+    after_dir=$(checkout after "$after")
+else
+    after_dir=$root
+fi
+build "$before_dir"
+build "$after_dir"
+for _ in $(seq "$runs"); do
+    time_build "$before_dir" "$tmp/before.times"
+    time_build "$after_dir" "$tmp/after.times"
+done
+echo "before ($before): $(summary "$tmp/before.times")"
+echo "after (${after:-working tree}): $(summary "$tmp/after.times")"
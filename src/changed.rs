@@ -0,0 +1,32 @@
+//! Telling the rest of the window that a matcher switched variants.
+
+use crate::VariantKind;
+use druid::{Selector, UpdateCtx, WidgetId};
+
+/// The payload of the `VARIANT_CHANGED` selector generated on every `{Enum}Kind`.
+///
+/// Whenever a matcher switches variants, it submits a command with this to its window,
+/// so every widget in the window, ancestors of the matcher included, can react to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VariantChanged<K> {
+    /// The variant shown before.
+    pub old: K,
+    /// The variant shown now.
+    pub new: K,
+    /// The matcher that switched.
+    pub widget: WidgetId,
+}
+
+pub fn submit_variant_changed<K: VariantKind>(
+    ctx: &mut UpdateCtx,
+    selector: Selector<VariantChanged<K>>,
+    old: K,
+    new: K,
+) {
+    let changed = VariantChanged {
+        old,
+        new,
+        widget: ctx.widget_id(),
+    };
+    ctx.submit_command(selector.with(changed).to(ctx.window_id()));
+}
@@ -0,0 +1,19 @@
+//! Papers over the differences between the supported druid versions.
+
+use druid::{BoxConstraints, Data, Env, LayoutCtx, Size, Widget, WidgetPod};
+
+/// Lays out the child and places it at the origin.
+pub fn layout_child<T: Data, W: Widget<T>>(
+    child: &mut WidgetPod<T, W>,
+    ctx: &mut LayoutCtx,
+    bc: &BoxConstraints,
+    data: &T,
+    env: &Env,
+) -> Size {
+    let size = child.layout(ctx, bc, data, env);
+    #[cfg(feature = "druid-0-7")]
+    child.set_layout_rect(ctx, data, env, size.to_rect());
+    #[cfg(feature = "druid-0-8")]
+    child.set_origin(ctx, druid::Point::ORIGIN);
+    size
+}
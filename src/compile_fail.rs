@@ -0,0 +1,100 @@
+//! Enums the derives have to reject, checked by the doc tests.
+
+/// A group can't take the name of the builder of another variant.
+///
+/// ```compile_fail
+/// # #[cfg(feature = "druid-0-7")]
+/// # extern crate druid_0_7 as druid;
+/// # #[cfg(feature = "druid-0-8")]
+/// # extern crate druid_0_8 as druid;
+/// use druid::Data;
+/// use druid_enums::Matcher;
+///
+/// #[derive(Clone, Data)]
+/// struct Payload;
+///
+/// #[derive(Clone, Data, Matcher)]
+/// enum Status {
+///     #[matcher(group = ready)]
+///     Loading,
+///     Ready(Payload),
+/// }
+/// ```
+pub struct GroupNamedLikeBuilder;
+
+/// The variants of a group need widgets for the same data.
+///
+/// ```compile_fail
+/// # #[cfg(feature = "druid-0-7")]
+/// # extern crate druid_0_7 as druid;
+/// # #[cfg(feature = "druid-0-8")]
+/// # extern crate druid_0_8 as druid;
+/// use druid::Data;
+/// use druid_enums::Matcher;
+///
+/// #[derive(Clone, Data)]
+/// struct Payload;
+///
+/// #[derive(Clone, Data, Matcher)]
+/// enum Status {
+///     #[matcher(group = busy)]
+///     Loading,
+///     #[matcher(group = busy)]
+///     Saving(Payload),
+/// }
+/// ```
+pub struct GroupWithDifferentData;
+
+/// The widget of a `deref` variant can't get its content along with the context,
+/// that would take a copy of the content on every pass.
+///
+/// ```compile_fail
+/// # #[cfg(feature = "druid-0-7")]
+/// # extern crate druid_0_7 as druid;
+/// # #[cfg(feature = "druid-0-8")]
+/// # extern crate druid_0_8 as druid;
+/// use druid::Data;
+/// use druid_enums::Matcher;
+/// use std::sync::Arc;
+///
+/// #[derive(Clone, Data)]
+/// struct Settings;
+///
+/// #[derive(Clone, Data)]
+/// struct Payload;
+///
+/// #[derive(Clone, Data, Matcher)]
+/// #[matcher(context = Settings)]
+/// enum Screen {
+///     #[matcher(deref)]
+///     Main(Arc<Payload>),
+/// }
+/// ```
+pub struct DerefWithContext;
+
+/// An arm of `match_widget!` can't bind the field of its variant, as it would never be
+/// in scope: the field is the data of the widget.
+///
+/// ```compile_fail
+/// # #[cfg(feature = "druid-0-7")]
+/// # extern crate druid_0_7 as druid;
+/// # #[cfg(feature = "druid-0-8")]
+/// # extern crate druid_0_8 as druid;
+/// use druid::{widget::SizedBox, Data, Widget};
+/// use druid_enums::match_widget;
+///
+/// #[derive(Clone, Data)]
+/// struct Payload;
+///
+/// #[derive(Clone, Data)]
+/// enum Status {
+///     Ready(Payload),
+/// }
+///
+/// fn ui() -> impl Widget<Status> {
+///     match_widget! { Status,
+///         Ready(payload) => SizedBox::<Payload>::empty(),
+///     }
+/// }
+/// ```
+pub struct MatchWidgetBinding;
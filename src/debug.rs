@@ -0,0 +1,49 @@
+//! Outlining matchers, for telling which variant is on screen.
+
+use crate::VariantKind;
+use druid::{
+    piet::{Text, TextLayoutBuilder},
+    Color, Env, Key, PaintCtx, Point, RenderContext,
+};
+
+/// Whether every matcher draws an outline naming its enum and active variant.
+///
+/// Unless set in the `Env`, this is `false`, or `true` with the `debug-paint` feature.
+pub const DEBUG_PAINT_MATCHERS: Key<bool> = Key::new("druid-enums.debug-paint-matchers");
+
+const OUTLINE_COLOR: Color = Color::rgb8(0xff, 0x00, 0xff);
+const OUTLINE_WIDTH: f64 = 1.0;
+
+/// The label naming the enum and active variant of a matcher, if its outline is drawn.
+pub fn debug_label<K: VariantKind>(env: &Env, enum_name: &str, kind: Option<K>) -> Option<String> {
+    let enabled = env
+        .try_get(&DEBUG_PAINT_MATCHERS)
+        .unwrap_or(cfg!(feature = "debug-paint"));
+    if !enabled {
+        return None;
+    }
+    let variant = kind.map(K::name).unwrap_or("?");
+    Some(format!("{}::{}", enum_name, variant))
+}
+
+pub fn debug_paint<K: VariantKind>(
+    ctx: &mut PaintCtx,
+    env: &Env,
+    enum_name: &str,
+    kind: Option<K>,
+) {
+    let text = match debug_label(env, enum_name, kind) {
+        Some(text) => text,
+        None => return,
+    };
+    let rect = ctx.size().to_rect().inset(OUTLINE_WIDTH / -2.0);
+    ctx.stroke(rect, &OUTLINE_COLOR, OUTLINE_WIDTH);
+    let layout = ctx
+        .text()
+        .new_text_layout(text)
+        .text_color(OUTLINE_COLOR)
+        .build();
+    if let Ok(layout) = layout {
+        ctx.draw_text(&layout, Point::new(OUTLINE_WIDTH, OUTLINE_WIDTH));
+    }
+}
@@ -0,0 +1,42 @@
+//! The payloads of `#[matcher(deref)]` variants, whose widgets get the content of the payload.
+
+use druid::Data;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// A shared pointer whose content a widget can change.
+pub trait DerefData: Deref {
+    /// The content, copied first while other clones of the pointer exist, see `Rc::make_mut`.
+    fn make_mut(this: &mut Self) -> &mut Self::Target
+    where
+        Self::Target: Data;
+
+    /// Calls `f` with a copy of the content, which replaces the content only if `f` changed it.
+    ///
+    /// Like druid's `InArc` lens, this keeps the pointer as long as nothing changes,
+    /// so comparing it with `Data::same` stays cheap.
+    fn with_mut<R>(this: &mut Self, f: impl FnOnce(&mut Self::Target) -> R) -> R
+    where
+        Self::Target: Data,
+    {
+        let mut content = (**this).clone();
+        let result = f(&mut content);
+        if !content.same(&**this) {
+            *Self::make_mut(this) = content;
+        }
+        result
+    }
+}
+
+impl<T: Data> DerefData for Rc<T> {
+    fn make_mut(this: &mut Self) -> &mut T {
+        Rc::make_mut(this)
+    }
+}
+
+impl<T: Data> DerefData for Arc<T> {
+    fn make_mut(this: &mut Self) -> &mut T {
+        Arc::make_mut(this)
+    }
+}
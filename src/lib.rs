@@ -1,261 +1,203 @@
-use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
-use syn::{parse_macro_input, Fields};
-
-mod parse;
-use parse::{MatcherDerive, MatcherVariant};
-
-#[proc_macro_derive(Matcher, attributes(matcher))]
-pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    // TODO when we generate a name that isn't a valid ident or is a keyword, generate a different
-    // name rather than panicking.
-    // TODO handle generics in the input
-    let input = parse_macro_input!(input as MatcherDerive);
-
-    let visibility = &input.visibility;
-    let enum_name = &input.enum_name;
-    let matcher_name = input.resolve_matcher_name();
-
-    // Returns the `T` in `Widget<T>` for the variant.
-    fn type_of(variant: &MatcherVariant) -> TokenStream {
-        match &variant.fields {
-            Fields::Unit => quote!(()),
-            Fields::Unnamed(fields) if fields.unnamed.is_empty() => quote!(()),
-            Fields::Unnamed(fields) => {
-                let types = fields.unnamed.iter().map(|f| &f.ty);
-                quote!((#(#types),*))
-            }
-            Fields::Named(_) => unreachable!(),
+//! Allows matching a `druid::Widget` to each variant of an enum.
+//!
+//! See [`Matcher`](derive.Matcher.html) for the derive and [`match_widget!`] for inline matching.
+//! [`WidgetEnum`](derive.WidgetEnum.html) goes the other way, for enums holding a widget in each variant.
+
+// Lets the derive be used inside of this crate.
+extern crate self as druid_enums;
+
+#[cfg(all(feature = "druid-0-7", feature = "druid-0-8"))]
+compile_error!("the `druid-0-7` and `druid-0-8` features can't be enabled at the same time");
+#[cfg(not(any(feature = "druid-0-7", feature = "druid-0-8")))]
+compile_error!("either the `druid-0-7` or the `druid-0-8` feature has to be enabled");
+
+#[cfg(feature = "druid-0-7")]
+extern crate druid_0_7 as druid;
+#[cfg(feature = "druid-0-8")]
+extern crate druid_0_8 as druid;
+
+use druid::{Command, Data, EventCtx, Widget};
+use std::{
+    any::Any,
+    error::Error,
+    fmt::{self, Debug},
+    hash::Hash,
+};
+
+pub use druid_enums_derive::{match_widget, Matcher, WidgetEnum};
+
+mod changed;
+mod compat;
+#[cfg(doctest)]
+mod compile_fail;
+mod debug;
+mod deref;
+mod match_widget;
+mod menu;
+mod missing;
+mod presentation;
+mod remote;
+mod replace;
+mod route;
+mod trace;
+mod unset;
+mod update;
+mod visit;
+mod wizard;
+
+pub use changed::VariantChanged;
+pub use debug::DEBUG_PAINT_MATCHERS;
+pub use menu::VariantMenu;
+pub use presentation::{Sizing, Transition};
+pub use remote::{OptionKind, OptionMatcher, ResultKind, ResultMatcher};
+pub use replace::{ReplaceWidget, REPLACE_VARIANT_WIDGET};
+pub use route::{split_route, NAVIGATE_TO_ROUTE};
+pub use unset::Unset;
+pub use wizard::{StepIndicator, Wizard};
+
+/// Implemented by every matcher generated with `#[derive(Matcher)]`.
+pub trait Matcher {
+    /// The enum this matcher is a widget for.
+    type Enum: Data;
+    /// The fieldless counterpart of the enum, see [`VariantKind`].
+    type Kind: VariantKind;
+
+    /// The kind of the variant currently shown, if the matcher has seen any data yet.
+    fn active_kind(&self) -> Option<Self::Kind>;
+
+    /// Whether a widget has been set for the variant, always true for `#[matcher(skip)]` variants.
+    fn has_widget_for(&self, kind: Self::Kind) -> bool;
+
+    /// Sets the widget for the variant.
+    ///
+    /// The widget must be a `Box<dyn Widget<T>>` with `T` being the data of the variant,
+    /// as created by [`box_widget`]. Otherwise it is handed back unchanged,
+    /// as it always is by matchers using `static_dispatch`, whose widgets are part of their type.
+    /// This has to happen before the matcher is added to the widget tree,
+    /// use [`replace_widget_boxed`](Matcher::replace_widget_boxed) afterwards.
+    fn set_widget_boxed(
+        &mut self,
+        kind: Self::Kind,
+        widget: Box<dyn Any>,
+    ) -> Result<(), Box<dyn Any>>;
+
+    /// Sets the widget for the variant while the matcher is part of the widget tree.
+    ///
+    /// If the variant is active, the new widget is added to the tree right away,
+    /// otherwise once the variant becomes active.
+    fn replace_widget_boxed(
+        &mut self,
+        ctx: &mut EventCtx,
+        kind: Self::Kind,
+        widget: Box<dyn Any>,
+    ) -> Result<(), Box<dyn Any>> {
+        self.set_widget_boxed(kind, widget)?;
+        if self.active_kind() == Some(kind) {
+            ctx.children_changed();
         }
+        Ok(())
     }
+}
 
-    // Returns (pattern to match for, `data` param for the widget).
-    fn data_of(variant: &MatcherVariant, prefix: &str) -> (TokenStream, TokenStream) {
-        match &variant.fields {
-            Fields::Unit => (quote!(), quote!(&mut ())),
-            Fields::Unnamed(fields) if fields.unnamed.is_empty() => (quote!(()), quote!(&mut ())),
-            Fields::Unnamed(fields) => {
-                let names: Vec<syn::Ident> = fields
-                    .unnamed
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| format_ident!("{}p{}", prefix, i))
-                    .collect();
-                (quote!((#(#names),*)), quote!((#(#names),*)))
-            }
-            Fields::Named(_) => unreachable!(),
-        }
-    }
-
-    let struct_fields = input.variants.iter().map(|variant| {
-        let builder_name = variant.resolve_builder_name();
-        let variant_ty = type_of(&variant);
-        quote!(#builder_name: Option<::druid::WidgetPod<#variant_ty, Box<dyn ::druid::Widget<#variant_ty>>>>)
-    });
-
-    let struct_defaults = input.variants.iter().map(|variant| {
-        let builder_name = variant.resolve_builder_name();
-        quote!(#builder_name: None)
-    });
-
-    let builder_fns = input.variants.iter().map(|variant| {
-        let builder_name = variant.resolve_builder_name();
-        let variant_ty = type_of(&variant);
-        quote! {
-            pub fn #builder_name(mut self, widget: impl ::druid::Widget<#variant_ty> + 'static) -> Self {
-                self.#builder_name = Some(::druid::WidgetPod::new(Box::new(widget)));
-                self
-            }
-        }
-    });
-
-    let widget_added_checks = input.variants.iter().map(|variant| {
-        let builder_name = variant.resolve_builder_name();
-        quote! {
-            if self.default_.is_none() && self.#builder_name.is_none() {
-                ::log::warn!("{}::{} variant of {:?} has not been set.", stringify!(#matcher_name), stringify!(#builder_name), ctx.widget_id());
-            }
-        }
-    });
-
-    let event_match = input.variants.iter().map(|variant| {
-        let builder_name = variant.resolve_builder_name();
-        let variant_name = &variant.name;
-        let (data_pattern, data_values) = data_of(&variant, "");
-        quote! {
-            #enum_name::#variant_name #data_pattern => match &mut self.#builder_name {
-                Some(widget) => widget.event(ctx, event, #data_values, env),
-                None => (),
-            }
-        }
-    });
-
-    let lifecycle_match = input.variants.iter().map(|variant| {
-        let builder_name = variant.resolve_builder_name();
-        let variant_name = &variant.name;
-        let (data_pattern, data_values) = data_of(&variant, "");
-        quote! {
-            #enum_name::#variant_name #data_pattern => match &mut self.#builder_name {
-                Some(widget) => widget.lifecycle(ctx, event, #data_values, env),
-                None => (),
-            }
-        }
-    });
+/// Implemented by every enum deriving `Matcher`, except for mirrors of foreign enums.
+///
+/// This is how `#[matcher(nested)]` variants reach the matcher of the enum inside of them.
+pub trait MatcherEnum: Data {
+    /// The fieldless counterpart of the enum, see [`VariantKind`].
+    type Kind: VariantKind;
+    /// The matcher of the enum, before any widgets are set.
+    type Matcher: Widget<Self>;
+
+    /// Creates a matcher without any widgets.
+    fn matcher() -> Self::Matcher;
+
+    /// Applies a `SWITCH_VARIANT` command of the enum, or of an enum nested in one of its variants.
+    ///
+    /// Returns whether the command was meant for the enum and it now is the requested variant.
+    fn apply_switch(&mut self, cmd: &Command) -> bool;
+
+    /// The builder names of the variant and of the variants nested in it, e.g. `["main", "inbox"]`.
+    fn route(&self) -> Vec<&'static str>;
+
+    /// Creates the data for a route, if every variant on it can be created,
+    /// i.e. is a unit variant, has an `init` or is nested and continues the route.
+    fn from_route(route: &[&str]) -> Option<Self>;
+
+    /// Navigates to the route, keeping the data of the variants that are already shown.
+    ///
+    /// Returns whether the route belongs to the enum and it now is at the route.
+    fn apply_route(&mut self, route: &[&str]) -> bool;
+
+    /// A menu with an item for each variant that can be switched to, see `{Enum}Kind::menu`.
+    fn menu<T: Data>(title: &str) -> VariantMenu<T>;
+}
 
-    let update_match = input.variants.iter().map(|variant| {
-        let builder_name = variant.resolve_builder_name();
-        let variant_name = &variant.name;
-        let (old_data_pattern, _old_data_values) = data_of(&variant, "old_");
-        let (data_pattern, data_values) = data_of(&variant, "");
-        quote! {
-            (#enum_name::#variant_name #old_data_pattern, #enum_name::#variant_name #data_pattern) => {
-                match &mut self.#builder_name {
-                    Some(widget) => widget.update(ctx, #data_values, env),
-                    None => (),
-                }
-            }
-        }
-    });
+/// A fieldless enum with one variant for each variant of a matched enum.
+///
+/// It is generated alongside the matcher and named `{Enum}Kind` by default.
+pub trait VariantKind: Copy + Eq + Hash + Debug + 'static {
+    /// All kinds in declaration order.
+    const ALL: &'static [Self];
 
-    let variant_update_match = input.variants.iter().map(|variant| {
-        let builder_name = variant.resolve_builder_name();
-        let variant_name = &variant.name;
-        let (data_pattern, data_values) = data_of(&variant, "");
-        quote! {
-            (_, #enum_name::#variant_name #data_pattern) => {
-                self.discriminant_ = Some(::std::mem::discriminant(data));
-                ctx.request_paint();
-                match &mut self.#builder_name {
-                     Some(widget) => match widget.is_initialized() {
-                        true => widget.update(ctx, #data_values, env),
-                        false => ctx.children_changed(),
-                    },
-                    None => (),
-                }
-            }
-        }
-    });
+    /// The name of the variant.
+    fn name(self) -> &'static str;
 
-    let layout_match = input.variants.iter().map(|variant| {
-        let builder_name = variant.resolve_builder_name();
-        let variant_name = &variant.name;
-        let (data_pattern, data_values) = data_of(&variant, "");
-        quote! {
-            #enum_name::#variant_name #data_pattern => match &mut self.#builder_name {
-                Some(widget) => {
-                    let size = widget.layout(ctx, bc, #data_values, env);
-                    widget.set_layout_rect(ctx, #data_values, env, size.to_rect());
-                    size
-                },
-                None => bc.min(),
-            }
-        }
-    });
+    /// The window title while the variant is shown, see `#[matcher(title = "...")]`.
+    /// Switching to a variant without a title keeps the current title of the window.
+    fn title(self) -> Option<&'static str> {
+        None
+    }
+}
 
-    let paint_match = input.variants.iter().map(|variant| {
-        let builder_name = variant.resolve_builder_name();
-        let variant_name = &variant.name;
-        let (data_pattern, data_values) = data_of(&variant, "");
-        quote! {
-            #enum_name::#variant_name #data_pattern => match &mut self.#builder_name {
-                Some(widget) => widget.paint(ctx, #data_values, env),
-                None => (),
-            }
-        }
-    });
+/// Boxes a widget for use with [`Matcher::set_widget_boxed`].
+pub fn box_widget<T: Data>(widget: impl Widget<T> + 'static) -> Box<dyn Any> {
+    let widget: Box<dyn Widget<T>> = Box::new(widget);
+    Box::new(widget)
+}
 
-    let output = quote! {
-        impl #enum_name {
-            pub fn matcher() -> #matcher_name {
-                #matcher_name::new()
-            }
-        }
+/// Returns the kinds of all variants without a widget.
+pub fn missing_widgets<M: Matcher>(matcher: &M) -> Vec<M::Kind> {
+    M::Kind::ALL
+        .iter()
+        .copied()
+        .filter(|kind| !matcher.has_widget_for(*kind))
+        .collect()
+}
 
-        #visibility struct #matcher_name {
-            #(#struct_fields,)*
-            default_: Option<Box<dyn ::druid::Widget<#enum_name>>>,
-            discriminant_: Option<::std::mem::Discriminant<#enum_name>>,
-        }
+/// The error returned by the `try_build` method of generated matchers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingVariants {
+    /// The name of the matcher.
+    pub matcher: &'static str,
+    /// The names of the variants without a widget.
+    pub variants: Vec<&'static str>,
+}
 
-        impl #matcher_name {
-            pub fn new() -> Self {
-                Self {
-                    #(#struct_defaults,)*
-                    default_: None,
-                    discriminant_: None,
-                }
-            }
-            pub fn default(mut self, widget: impl ::druid::Widget<#enum_name> + 'static) -> Self {
-                self.default_ = Some(Box::new(widget));
-                self
-            }
-            pub fn default_empty(mut self) -> Self {
-                self.default_ = Some(Box::new(::druid::widget::SizedBox::empty()));
-                self
-            }
-            #(#builder_fns)*
-        }
+impl fmt::Display for MissingVariants {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} has no widget for the variants {}",
+            self.matcher,
+            self.variants.join(", ")
+        )
+    }
+}
 
-        impl ::druid::Widget<#enum_name> for #matcher_name {
-            fn event(
-                &mut self,
-                ctx: &mut ::druid::EventCtx,
-                event: &::druid::Event,
-                data: &mut #enum_name,
-                env: &::druid::Env
-            ) {
-                if self.discriminant_ == Some(::std::mem::discriminant(data)) {
-                    match data {
-                        #(#event_match)*
-                    }
-                }
-            }
-            fn lifecycle(
-                &mut self,
-                ctx: &mut ::druid::LifeCycleCtx,
-                event: &::druid::LifeCycle,
-                data: &#enum_name,
-                env: &::druid::Env
-            ) {
-                self.discriminant_ = Some(::std::mem::discriminant(data));
-                if let ::druid::LifeCycle::WidgetAdded = event {
-                    #(#widget_added_checks)*
-                }
-                match data {
-                    #(#lifecycle_match)*
-                }
-            }
-            fn update(&mut self,
-                ctx: &mut ::druid::UpdateCtx,
-                old_data: &#enum_name,
-                data: &#enum_name,
-                env: &::druid::Env
-            ) {
-                match (old_data, data) {
-                    #(#update_match)*
-                    #(#variant_update_match)*
-                    _ => {
-                        unreachable!("Some variant is missing");
-                    }
-                }
-            }
-            fn layout(
-                &mut self,
-                ctx: &mut ::druid::LayoutCtx,
-                bc: &::druid::BoxConstraints,
-                data: &#enum_name,
-                env: &::druid::Env
-            ) -> ::druid::Size {
-                match data {
-                    #(#layout_match)*
-                }
-            }
-            fn paint(&mut self, ctx: &mut ::druid::PaintCtx, data: &#enum_name, env: &::druid::Env) {
-                match data {
-                    #(#paint_match)*
-                }
-            }
-        }
-    };
-    output.into()
+impl Error for MissingVariants {}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::compat::layout_child;
+    pub use crate::debug::{debug_label, debug_paint};
+    pub use crate::deref::DerefData;
+    pub use crate::match_widget::{MatchArm, MatchUnitArm, MatchWidget};
+    pub use crate::menu::{set_window_title, variant_menu, VariantMenuItem};
+    pub use crate::missing::{panic_missing, Placeholder};
+    pub use crate::presentation::Presentation;
+    pub use crate::replace::set_boxed_widget;
+    pub use crate::trace::{variant_span, variant_switched, widget_missing};
+    pub use crate::unset::is_unset;
+    pub use crate::update::UpdateEffects;
+    pub use crate::visit::{Visit, VisitChild, VisitChildMut, VisitEvent};
 }
@@ -0,0 +1,180 @@
+//! The widgets `match_widget!` expands to.
+
+use crate::compat::layout_child;
+use druid::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Size,
+    UpdateCtx, Widget, WidgetPod,
+};
+use std::marker::PhantomData;
+
+type Arm<E> = (fn(&E) -> bool, WidgetPod<E, Box<dyn Widget<E>>>);
+
+/// Dispatches to the first arm matching the data.
+pub struct MatchWidget<E> {
+    arms: Vec<Arm<E>>,
+    active: Option<usize>,
+}
+
+impl<E: Data> MatchWidget<E> {
+    pub fn new() -> Self {
+        MatchWidget {
+            arms: Vec::new(),
+            active: None,
+        }
+    }
+
+    pub fn arm(mut self, matches: fn(&E) -> bool, widget: impl Widget<E> + 'static) -> Self {
+        self.arms.push((matches, WidgetPod::new(Box::new(widget))));
+        self
+    }
+
+    fn position(&self, data: &E) -> Option<usize> {
+        self.arms.iter().position(|(matches, _)| matches(data))
+    }
+}
+
+impl<E: Data> Default for MatchWidget<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Data> Widget<E> for MatchWidget<E> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut E, env: &Env) {
+        if let Some(active) = self.active {
+            if self.position(data) == Some(active) {
+                self.arms[active].1.event(ctx, event, data, env);
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &E, env: &Env) {
+        self.active = self.position(data);
+        if let Some(active) = self.active {
+            self.arms[active].1.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &E, data: &E, env: &Env) {
+        let active = self.position(data);
+        if active != self.active {
+            self.active = active;
+            ctx.request_layout();
+            if let Some(active) = active {
+                if !self.arms[active].1.is_initialized() {
+                    ctx.children_changed();
+                    return;
+                }
+            }
+        }
+        if let Some(active) = active {
+            self.arms[active].1.update(ctx, data, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &E, env: &Env) -> Size {
+        match self.active {
+            Some(active) => layout_child(&mut self.arms[active].1, ctx, bc, data, env),
+            None => bc.min(),
+        }
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &E, env: &Env) {
+        if let Some(active) = self.active {
+            self.arms[active].1.paint(ctx, data, env);
+        }
+    }
+}
+
+/// Projects the enum onto the single field of a variant.
+pub struct MatchArm<E, T, W> {
+    get: fn(&E) -> Option<&T>,
+    get_mut: fn(&mut E) -> Option<&mut T>,
+    widget: W,
+}
+
+impl<E, T, W: Widget<T>> MatchArm<E, T, W> {
+    pub fn new(
+        get: fn(&E) -> Option<&T>,
+        get_mut: fn(&mut E) -> Option<&mut T>,
+        widget: W,
+    ) -> Self {
+        MatchArm {
+            get,
+            get_mut,
+            widget,
+        }
+    }
+}
+
+impl<E, T, W: Widget<T>> Widget<E> for MatchArm<E, T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut E, env: &Env) {
+        if let Some(data) = (self.get_mut)(data) {
+            self.widget.event(ctx, event, data, env);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &E, env: &Env) {
+        if let Some(data) = (self.get)(data) {
+            self.widget.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &E, data: &E, env: &Env) {
+        match ((self.get)(old_data), (self.get)(data)) {
+            (Some(old_data), Some(data)) => self.widget.update(ctx, old_data, data, env),
+            (None, Some(_)) => ctx.request_layout(),
+            _ => (),
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &E, env: &Env) -> Size {
+        match (self.get)(data) {
+            Some(data) => self.widget.layout(ctx, bc, data, env),
+            None => bc.min(),
+        }
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &E, env: &Env) {
+        if let Some(data) = (self.get)(data) {
+            self.widget.paint(ctx, data, env);
+        }
+    }
+}
+
+/// Hands `()` to the widget of a unit variant.
+pub struct MatchUnitArm<E, W> {
+    widget: W,
+    enum_: PhantomData<fn(E)>,
+}
+
+impl<E, W: Widget<()>> MatchUnitArm<E, W> {
+    pub fn new(widget: W) -> Self {
+        MatchUnitArm {
+            widget,
+            enum_: PhantomData,
+        }
+    }
+}
+
+impl<E, W: Widget<()>> Widget<E> for MatchUnitArm<E, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut E, env: &Env) {
+        self.widget.event(ctx, event, &mut (), env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &E, env: &Env) {
+        self.widget.lifecycle(ctx, event, &(), env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &E, _data: &E, env: &Env) {
+        self.widget.update(ctx, &(), &(), env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &E, env: &Env) -> Size {
+        self.widget.layout(ctx, bc, &(), env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &E, env: &Env) {
+        self.widget.paint(ctx, &(), env);
+    }
+}
@@ -0,0 +1,72 @@
+//! Window titles and menus following the variants of an enum.
+
+use crate::VariantKind;
+use druid::{Data, Selector, WindowHandle};
+
+#[cfg(feature = "druid-0-7")]
+use druid::{LocalizedString, MenuDesc, MenuItem};
+#[cfg(feature = "druid-0-8")]
+use druid::{Menu, MenuItem};
+
+/// The menu created by the `menu` function of every `{Enum}Kind`.
+///
+/// This is a `MenuDesc` with druid 0.7 and a `Menu` with druid 0.8.
+#[cfg(feature = "druid-0-7")]
+pub type VariantMenu<T> = MenuDesc<T>;
+/// The menu created by the `menu` function of every `{Enum}Kind`.
+///
+/// This is a `MenuDesc` with druid 0.7 and a `Menu` with druid 0.8.
+#[cfg(feature = "druid-0-8")]
+pub type VariantMenu<T> = Menu<T>;
+
+/// An entry of the menu created by [`variant_menu`].
+pub enum VariantMenuItem<K, T> {
+    /// An item submitting the selector with the kind.
+    Switch(K),
+    /// The menu of the enum nested in the variant, created with the label of the variant.
+    Nested(K, fn(&str) -> VariantMenu<T>),
+}
+
+/// A menu with the `items`, where `Switch` items submit `selector` with their kind.
+///
+/// Entries are labelled with the title of the variant, or its name if it has none.
+pub fn variant_menu<K: VariantKind, T: Data>(
+    title: &str,
+    selector: Selector<K>,
+    items: Vec<VariantMenuItem<K, T>>,
+) -> VariantMenu<T> {
+    let label = |kind: K| kind.title().unwrap_or_else(|| kind.name()).to_string();
+    #[cfg(feature = "druid-0-7")]
+    {
+        let title = LocalizedString::new("druid-enums.variant-menu").with_placeholder(title);
+        items
+            .into_iter()
+            .fold(MenuDesc::new(title), |menu, item| match item {
+                VariantMenuItem::Switch(kind) => {
+                    let item = LocalizedString::new("druid-enums.variant-menu-item")
+                        .with_placeholder(label(kind));
+                    menu.append(MenuItem::new(item, selector.with(kind)))
+                }
+                VariantMenuItem::Nested(kind, nested) => menu.append(nested(&label(kind))),
+            })
+    }
+    #[cfg(feature = "druid-0-8")]
+    {
+        items
+            .into_iter()
+            .fold(Menu::new(title.to_string()), |menu, item| match item {
+                VariantMenuItem::Switch(kind) => {
+                    menu.entry(MenuItem::new(label(kind)).command(selector.with(kind)))
+                }
+                VariantMenuItem::Nested(kind, nested) => menu.entry(nested(&label(kind))),
+            })
+    }
+}
+
+/// Sets the title of the window to the title of the variant. Variants without a title leave
+/// the window with the title it already has, which may be the one of the previous variant.
+pub fn set_window_title<K: VariantKind>(window: &WindowHandle, kind: K) {
+    if let Some(title) = kind.title() {
+        window.set_title(title);
+    }
+}
@@ -0,0 +1,48 @@
+//! What generated matchers do about variants without a widget, see `#[matcher(missing = ...)]`.
+
+use druid::{
+    widget::Label, BoxConstraints, Color, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, RenderContext, Size, UpdateCtx, Widget,
+};
+
+pub fn panic_missing(matcher: &str, builder: &str) -> ! {
+    panic!("{}::{} variant has not been set.", matcher, builder)
+}
+
+/// Shown in place of a variant without a widget, naming the variant.
+pub struct Placeholder {
+    label: Label<()>,
+}
+
+impl Placeholder {
+    pub fn new(enum_name: &str, variant_name: &str) -> Self {
+        let text = format!("{}::{} has no widget", enum_name, variant_name);
+        Placeholder {
+            label: Label::new(text).with_text_color(Color::BLACK),
+        }
+    }
+}
+
+impl<T> Widget<T> for Placeholder {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, env: &Env) {
+        self.label.event(ctx, event, &mut (), env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &T, env: &Env) {
+        self.label.lifecycle(ctx, event, &(), env);
+    }
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {}
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        let label_size = self.label.layout(ctx, bc, &(), env);
+        bc.constrain(label_size)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        let rect = ctx.size().to_rect();
+        ctx.fill(rect, &Color::rgb8(0xff, 0xd0, 0x40));
+        ctx.stroke(rect.inset(-1.0), &Color::rgb8(0xe0, 0x20, 0x20), 2.0);
+        self.label.paint(ctx, &(), env);
+    }
+}
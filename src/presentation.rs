@@ -0,0 +1,123 @@
+//! How matchers are sized and how they switch between variants.
+
+use druid::{Affine, BoxConstraints, Event, EventCtx, PaintCtx, RenderContext, Size};
+use std::time::Duration;
+
+/// How a matcher is sized, set with the `with_sizing` method of generated matchers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sizing {
+    /// The size of the widget of the active variant.
+    Active,
+    /// The largest size of the widgets shown so far, so that the matcher doesn't shrink
+    /// when switching to a variant with a smaller widget.
+    ///
+    /// Only the widget of the active variant is laid out, so variants that haven't been
+    /// shown yet don't count.
+    Largest,
+}
+
+impl Default for Sizing {
+    fn default() -> Self {
+        Sizing::Active
+    }
+}
+
+/// How a matcher switches between variants, set with the `with_transition` method of
+/// generated matchers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transition {
+    /// Shows the widget of the new variant right away.
+    Instant,
+    /// Slides the widget of the new variant in from the right, taking the given time.
+    Slide(Duration),
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Transition::Instant
+    }
+}
+
+/// The sizing and transition of a matcher, along with what they need to remember.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Presentation {
+    pub sizing: Sizing,
+    pub transition: Transition,
+    largest: Size,
+    /// How long the running transition has taken so far.
+    elapsed: Option<Duration>,
+}
+
+impl Presentation {
+    /// Starts the transition to a new variant and returns whether it needs animation frames.
+    pub fn start(&mut self) -> bool {
+        self.elapsed = match self.transition {
+            Transition::Slide(duration) if duration > Duration::default() => {
+                Some(Duration::default())
+            }
+            _ => None,
+        };
+        self.elapsed.is_some()
+    }
+
+    /// Advances the running transition by the `interval` of an `AnimFrame` in nanoseconds,
+    /// and returns whether it needs more frames.
+    pub fn advance(&mut self, interval: u64) -> bool {
+        if let (Some(elapsed), Transition::Slide(duration)) = (self.elapsed, self.transition) {
+            let elapsed = elapsed + Duration::from_nanos(interval);
+            self.elapsed = Some(elapsed).filter(|elapsed| *elapsed < duration);
+        }
+        self.elapsed.is_some()
+    }
+
+    /// How far the widget of the active variant is still shifted to the right,
+    /// as a fraction of the width of the matcher.
+    pub fn offset(&self) -> f64 {
+        match (self.elapsed, self.transition) {
+            (Some(elapsed), Transition::Slide(duration)) => {
+                1.0 - elapsed.as_secs_f64() / duration.as_secs_f64()
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// The size of the matcher, given the size of the widget of the active variant.
+    pub fn size(&mut self, child: Size, bc: &BoxConstraints) -> Size {
+        match self.sizing {
+            Sizing::Active => child,
+            Sizing::Largest => {
+                self.largest = Size::new(
+                    self.largest.width.max(child.width),
+                    self.largest.height.max(child.height),
+                );
+                bc.constrain(self.largest)
+            }
+        }
+    }
+
+    /// Moves the running transition along on `AnimFrame` events.
+    pub fn event(&mut self, ctx: &mut EventCtx, event: &Event) {
+        if let Event::AnimFrame(interval) = event {
+            if self.elapsed.is_some() {
+                ctx.request_paint();
+                if self.advance(*interval) {
+                    ctx.request_anim_frame();
+                }
+            }
+        }
+    }
+
+    /// Lets `paint` paint the widget of the active variant, shifted by the running transition.
+    pub fn paint(&self, ctx: &mut PaintCtx, paint: impl FnOnce(&mut PaintCtx)) {
+        let offset = self.offset();
+        if offset == 0.0 {
+            return paint(ctx);
+        }
+        let size = ctx.size();
+        ctx.with_save(|ctx| {
+            ctx.clip(size.to_rect());
+            ctx.transform(Affine::translate((offset * size.width, 0.0)));
+            paint(ctx);
+        });
+    }
+}
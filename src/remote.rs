@@ -0,0 +1,19 @@
+//! Matchers for enums of the standard library.
+
+#![allow(dead_code)]
+
+use crate::Matcher;
+
+#[derive(Matcher)]
+#[matcher(remote = "Option<T>", matcher_name = OptionMatcher, kind_name = OptionKind)]
+pub enum OptionMirror<T> {
+    Some(T),
+    None,
+}
+
+#[derive(Matcher)]
+#[matcher(remote = "Result<T, E>", matcher_name = ResultMatcher, kind_name = ResultKind)]
+pub enum ResultMirror<T, E> {
+    Ok(T),
+    Err(E),
+}
@@ -0,0 +1,66 @@
+use crate::VariantKind;
+use druid::{Data, Selector, Widget, WidgetPod};
+use std::{any::Any, cell::RefCell};
+
+/// Replaces the widget of a variant in a matcher that is already part of the widget tree.
+///
+/// The first matcher with a matching `Kind` and variant data type to receive the command takes
+/// the widget, so it is usually sent to the `WidgetId` of a specific matcher.
+///
+/// ```ignore
+/// let replacement = ReplaceWidget::new(AppStateKind::Main, compact_main_ui());
+/// ctx.submit_command(REPLACE_VARIANT_WIDGET.with(replacement).to(matcher_id));
+/// ```
+pub const REPLACE_VARIANT_WIDGET: Selector<ReplaceWidget> =
+    Selector::new("druid-enums.replace-variant-widget");
+
+/// The payload of [`REPLACE_VARIANT_WIDGET`].
+pub struct ReplaceWidget {
+    kind: Box<dyn Any>,
+    widget: RefCell<Option<Box<dyn Any>>>,
+}
+
+impl ReplaceWidget {
+    pub fn new<K: VariantKind, T: Data>(kind: K, widget: impl Widget<T> + 'static) -> Self {
+        ReplaceWidget {
+            kind: Box::new(kind),
+            widget: RefCell::new(Some(crate::box_widget(widget))),
+        }
+    }
+
+    /// Hands the widget to `replace` if the kind is a `K` and the widget hasn't been taken yet.
+    ///
+    /// Returns `true` if `replace` accepted the widget.
+    pub fn apply<K: VariantKind>(
+        &self,
+        replace: impl FnOnce(K, Box<dyn Any>) -> Result<(), Box<dyn Any>>,
+    ) -> bool {
+        let kind = match self.kind.downcast_ref::<K>() {
+            Some(kind) => *kind,
+            None => return false,
+        };
+        let mut slot = self.widget.borrow_mut();
+        let widget = match slot.take() {
+            Some(widget) => widget,
+            None => return false,
+        };
+        match replace(kind, widget) {
+            Ok(()) => true,
+            Err(widget) => {
+                *slot = Some(widget);
+                false
+            }
+        }
+    }
+}
+
+/// Puts a widget boxed by [`crate::box_widget`] into the slot of a variant, if it is a
+/// widget for `T`.
+pub fn set_boxed_widget<T: Data>(
+    slot: &mut Option<WidgetPod<T, Box<dyn Widget<T>>>>,
+    widget: Box<dyn Any>,
+) -> Result<(), Box<dyn Any>> {
+    let widget = widget.downcast::<Box<dyn Widget<T>>>()?;
+    *slot = Some(WidgetPod::new(*widget));
+    Ok(())
+}
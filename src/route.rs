@@ -0,0 +1,22 @@
+//! Path-style routes like `main/settings/network`, naming a variant and those nested in it.
+
+use druid::Selector;
+
+/// Navigates the first matcher whose enum has the route to it.
+///
+/// The route is made of the builder names of the variants, separated by `/`, starting with
+/// the outermost enum and going through its `#[matcher(nested)]` variants. Variants already
+/// shown keep their data, the others are created like `{Enum}Kind::from_route` does.
+///
+/// ```ignore
+/// ctx.submit_command(NAVIGATE_TO_ROUTE.with("main/settings/network".to_string()));
+/// ```
+pub const NAVIGATE_TO_ROUTE: Selector<String> = Selector::new("druid-enums.navigate-to-route");
+
+/// The segments of a route, ignoring leading, trailing and repeated slashes.
+pub fn split_route(route: &str) -> Vec<&str> {
+    route
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
@@ -0,0 +1,57 @@
+//! Instrumentation of generated matchers, only active with the `tracing` feature.
+
+use crate::VariantKind;
+
+/// Keeps the span of a `layout` or `paint` call entered until it is dropped.
+#[must_use]
+pub struct VariantSpan {
+    #[cfg(feature = "tracing")]
+    _entered: tracing::span::EnteredSpan,
+}
+
+/// Enters a span covering `method` of the matcher, recording the active variant.
+pub fn variant_span<K: VariantKind>(
+    matcher: &'static str,
+    method: &'static str,
+    kind: Option<K>,
+) -> VariantSpan {
+    #[cfg(feature = "tracing")]
+    {
+        let variant = kind.map(K::name).unwrap_or("none");
+        let span = tracing::trace_span!("druid_enums", matcher, method, variant);
+        VariantSpan {
+            _entered: span.entered(),
+        }
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (matcher, method, kind);
+        VariantSpan {}
+    }
+}
+
+/// Records that the matcher switched from the `old` to the `new` variant.
+pub fn variant_switched<K: VariantKind>(matcher: &'static str, old: K, new: K) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        matcher,
+        old = old.name(),
+        new = new.name(),
+        "variant switched"
+    );
+    #[cfg(not(feature = "tracing"))]
+    let _ = (matcher, old, new);
+}
+
+/// Reports a variant without a widget.
+pub fn widget_missing(matcher: &str, builder: &str, id: druid::WidgetId) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(matcher, builder, widget = ?id, "variant has not been set");
+    #[cfg(not(feature = "tracing"))]
+    log::warn!(
+        "{}::{} variant of {:?} has not been set.",
+        matcher,
+        builder,
+        id
+    );
+}
@@ -0,0 +1,32 @@
+//! The placeholder for variants of a `static_dispatch` matcher without a widget.
+
+use druid::{
+    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Size,
+    UpdateCtx, Widget,
+};
+use std::any::{Any, TypeId};
+
+/// The widget of every variant of a `static_dispatch` matcher until its builder is called.
+///
+/// It takes up as little space as possible and shows nothing.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Unset;
+
+impl<T> Widget<T> for Unset {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &T, _env: &Env) {}
+
+    fn update(&mut self, _ctx: &mut UpdateCtx, _old_data: &T, _data: &T, _env: &Env) {}
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, _env: &Env) -> Size {
+        bc.min()
+    }
+
+    fn paint(&mut self, _ctx: &mut PaintCtx, _data: &T, _env: &Env) {}
+}
+
+/// Whether `W` is the [`Unset`] placeholder.
+pub fn is_unset<W: Any>() -> bool {
+    TypeId::of::<W>() == TypeId::of::<Unset>()
+}
@@ -0,0 +1,47 @@
+//! What a matcher does to its `UpdateCtx` when its data changes, besides updating its child.
+
+use crate::{changed::submit_variant_changed, menu::set_window_title, VariantChanged, VariantKind};
+use druid::{Key, Selector, UpdateCtx};
+
+/// The parts of an `UpdateCtx` a matcher uses to react to a change of its data.
+///
+/// Matchers only go through this, so tests can hand them something recording what they did.
+pub trait UpdateEffects {
+    fn env_key_changed(&self, key: &Key<bool>) -> bool;
+    fn request_paint(&mut self);
+    fn request_layout(&mut self);
+    fn request_anim_frame(&mut self);
+    fn set_window_title<K: VariantKind>(&mut self, kind: K);
+    fn submit_variant_changed<K: VariantKind>(
+        &mut self,
+        selector: Selector<VariantChanged<K>>,
+        old: K,
+        new: K,
+    );
+}
+
+impl UpdateEffects for UpdateCtx<'_, '_> {
+    fn env_key_changed(&self, key: &Key<bool>) -> bool {
+        UpdateCtx::env_key_changed(self, key)
+    }
+    fn request_paint(&mut self) {
+        UpdateCtx::request_paint(self)
+    }
+    fn request_layout(&mut self) {
+        UpdateCtx::request_layout(self)
+    }
+    fn request_anim_frame(&mut self) {
+        UpdateCtx::request_anim_frame(self)
+    }
+    fn set_window_title<K: VariantKind>(&mut self, kind: K) {
+        set_window_title(self.window(), kind)
+    }
+    fn submit_variant_changed<K: VariantKind>(
+        &mut self,
+        selector: Selector<VariantChanged<K>>,
+        old: K,
+        new: K,
+    ) {
+        submit_variant_changed(self, selector, old, new)
+    }
+}
@@ -0,0 +1,91 @@
+//! The `Widget` methods of generated matchers, written once for all of them.
+//!
+//! A generated matcher only knows how to find the child of its active variant and the data
+//! of that child, see `with_active_child` and `with_active_child_mut`. It hands both
+//! to one of the visitors here, which are generic over the child, so nothing is dynamic.
+
+use crate::compat::layout_child;
+use druid::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Size,
+    UpdateCtx, Widget, WidgetPod,
+};
+
+/// Does something with the child of the active variant.
+pub trait VisitChild {
+    type Output;
+
+    fn visit<T: Data, W: Widget<T>>(self, child: &mut WidgetPod<T, W>, data: &T) -> Self::Output;
+}
+
+/// Does something with the child of the active variant, which may change its data.
+pub trait VisitChildMut {
+    type Output;
+
+    fn visit_mut<T: Data, W: Widget<T>>(
+        self,
+        child: &mut WidgetPod<T, W>,
+        data: &mut T,
+    ) -> Self::Output;
+}
+
+pub struct VisitEvent<'a, 'b, 'c> {
+    pub ctx: &'a mut EventCtx<'b, 'c>,
+    pub event: &'a Event,
+    pub env: &'a Env,
+}
+
+impl VisitChildMut for VisitEvent<'_, '_, '_> {
+    type Output = ();
+
+    fn visit_mut<T: Data, W: Widget<T>>(self, child: &mut WidgetPod<T, W>, data: &mut T) {
+        child.event(self.ctx, self.event, data, self.env);
+    }
+}
+
+/// Visits the child of the active variant for one of the `Widget` methods that only read
+/// the data.
+///
+/// Being one type for all of them, it makes for one copy of `with_active_child` per matcher.
+pub enum Visit<'a, 'b, 'c, 'd> {
+    LifeCycle {
+        ctx: &'a mut LifeCycleCtx<'b, 'c>,
+        event: &'a LifeCycle,
+        env: &'a Env,
+    },
+    Update {
+        ctx: &'a mut UpdateCtx<'b, 'c>,
+        env: &'a Env,
+        /// Whether the variant differs from the one of the old data.
+        switched: bool,
+    },
+    Layout {
+        ctx: &'a mut LayoutCtx<'b, 'c>,
+        bc: &'a BoxConstraints,
+        env: &'a Env,
+    },
+    Paint {
+        ctx: &'a mut PaintCtx<'b, 'c, 'd>,
+        env: &'a Env,
+    },
+}
+
+impl VisitChild for Visit<'_, '_, '_, '_> {
+    /// The size of the child for `Layout`, zero otherwise.
+    type Output = Size;
+
+    fn visit<T: Data, W: Widget<T>>(self, child: &mut WidgetPod<T, W>, data: &T) -> Size {
+        match self {
+            Visit::LifeCycle { ctx, event, env } => child.lifecycle(ctx, event, data, env),
+            Visit::Update { ctx, env, switched } => {
+                if switched && !child.is_initialized() {
+                    ctx.children_changed();
+                } else {
+                    child.update(ctx, data, env);
+                }
+            }
+            Visit::Layout { ctx, bc, env } => return layout_child(child, ctx, bc, data, env),
+            Visit::Paint { ctx, env } => child.paint(ctx, data, env),
+        }
+        Size::ZERO
+    }
+}
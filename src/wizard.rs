@@ -0,0 +1,54 @@
+//! Enums whose variants are the ordered steps of a wizard, see `#[matcher(wizard)]`.
+
+use druid::{
+    kurbo::Circle, theme, BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, RenderContext, Size, UpdateCtx, Widget,
+};
+
+/// Implemented by every enum deriving `Matcher` with `#[matcher(wizard)]`.
+///
+/// Its matcher moves between the steps on the `NEXT_STEP` and `PREVIOUS_STEP` commands
+/// of the generated `{Enum}Kind`.
+pub trait Wizard: Data {
+    /// The position of the current step, starting at 0.
+    fn step_index(&self) -> usize;
+    /// The number of steps, i.e. variants of the enum.
+    fn step_count() -> usize;
+}
+
+const DOT_RADIUS: f64 = 4.0;
+const DOT_SPACING: f64 = 16.0;
+
+/// A row of dots, one for each step of a wizard, filled up to the current step.
+pub struct StepIndicator;
+
+impl<T: Wizard> Widget<T> for StepIndicator {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &T, _env: &Env) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, _env: &Env) {
+        if old_data.step_index() != data.step_index() {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, _env: &Env) -> Size {
+        bc.constrain(Size::new(
+            T::step_count() as f64 * DOT_SPACING,
+            DOT_RADIUS * 2.0,
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let y = ctx.size().height / 2.0;
+        for step in 0..T::step_count() {
+            let center = Point::new((step as f64 + 0.5) * DOT_SPACING, y);
+            let color = match step <= data.step_index() {
+                true => env.get(theme::PRIMARY_LIGHT),
+                false => env.get(theme::BORDER_DARK),
+            };
+            ctx.fill(Circle::new(center, DOT_RADIUS), &color);
+        }
+    }
+}
@@ -1,5 +1,19 @@
-use druid::{widget::SizedBox, Data, Widget};
-use druid_enums::Matcher;
+#[cfg(feature = "druid-0-7")]
+extern crate druid_0_7 as druid;
+#[cfg(feature = "druid-0-8")]
+extern crate druid_0_8 as druid;
+
+use druid::{
+    widget::SizedBox, ArcStr, BoxConstraints, Data, Env, EventCtx, Key, Lens, LensExt, Selector,
+    Size, Widget, WidgetId, WidgetPod,
+};
+use druid_enums::{
+    __private::{debug_label, DerefData, Presentation, UpdateEffects, VisitChild, VisitChildMut},
+    box_widget, missing_widgets, Matcher, OptionKind, ReplaceWidget, Sizing, Transition,
+    VariantChanged, VariantKind, VariantMenu, DEBUG_PAINT_MATCHERS, REPLACE_VARIANT_WIDGET,
+};
+use std::any::Any;
+use std::time::Duration;
 
 #[derive(Clone, Data)]
 struct A;
@@ -47,3 +61,588 @@ fn generated_matcher_name() {
         .a(SizedBox::<A>::empty())
         .b(SizedBox::<B>::empty());
 }
+
+#[test]
+fn kinds() {
+    assert_eq!(ABKind::of(&AB::A(A)), ABKind::A);
+    assert_eq!(ABKind::ALL, &[ABKind::A, ABKind::B]);
+    assert_eq!(ABKind::B.name(), "B");
+}
+
+/// Records what a matcher asks of its `UpdateCtx`.
+#[derive(Default)]
+struct RecordedUpdate {
+    changed_keys: Vec<ArcStr>,
+    paints: usize,
+    layouts: usize,
+    anim_frames: usize,
+    titles: Vec<Option<&'static str>>,
+    variant_changes: Vec<(&'static str, &'static str)>,
+}
+
+impl UpdateEffects for RecordedUpdate {
+    fn env_key_changed(&self, key: &Key<bool>) -> bool {
+        self.changed_keys.contains(&ArcStr::from(key.clone()))
+    }
+    fn request_paint(&mut self) {
+        self.paints += 1;
+    }
+    fn request_layout(&mut self) {
+        self.layouts += 1;
+    }
+    fn request_anim_frame(&mut self) {
+        self.anim_frames += 1;
+    }
+    fn set_window_title<K: VariantKind>(&mut self, kind: K) {
+        self.titles.push(kind.title());
+    }
+    fn submit_variant_changed<K: VariantKind>(
+        &mut self,
+        _: Selector<VariantChanged<K>>,
+        old: K,
+        new: K,
+    ) {
+        self.variant_changes.push((old.name(), new.name()));
+    }
+}
+
+#[test]
+fn debug_paint_matchers() {
+    let mut matcher = AB::matcher();
+    let mut ctx = RecordedUpdate::default();
+    assert!(!matcher.update_variant(&mut ctx, ABKind::A, ABKind::A));
+    assert_eq!(ctx.paints, 0);
+
+    ctx.changed_keys.push(DEBUG_PAINT_MATCHERS.into());
+    assert!(!matcher.update_variant(&mut ctx, ABKind::A, ABKind::A));
+    assert_eq!(ctx.paints, 1);
+    assert!(ctx.variant_changes.is_empty());
+
+    let mut env = Env::default();
+    let unset = debug_label(&env, "AB", Some(ABKind::B));
+    assert_eq!(unset.is_some(), cfg!(feature = "debug-paint"));
+    env.set(DEBUG_PAINT_MATCHERS, true);
+    assert_eq!(
+        debug_label(&env, "AB", Some(ABKind::B)).as_deref(),
+        Some("AB::B")
+    );
+    assert_eq!(
+        debug_label::<ABKind>(&env, "AB", None).as_deref(),
+        Some("AB::?")
+    );
+    env.set(DEBUG_PAINT_MATCHERS, false);
+    assert_eq!(debug_label(&env, "AB", Some(ABKind::B)), None);
+}
+
+#[test]
+fn variant_changed() {
+    fn is_selector<T>(_: Selector<T>) {}
+    is_selector::<VariantChanged<ABKind>>(ABKind::VARIANT_CHANGED);
+    is_selector::<VariantChanged<OptionKind>>(OptionKind::VARIANT_CHANGED);
+
+    let mut matcher = AB::matcher();
+    let mut ctx = RecordedUpdate::default();
+    assert!(!matcher.update_variant(&mut ctx, ABKind::A, ABKind::A));
+    assert!(ctx.variant_changes.is_empty());
+    assert_eq!(ctx.paints, 0);
+
+    assert!(matcher.update_variant(&mut ctx, ABKind::A, ABKind::B));
+    assert_eq!(ctx.variant_changes, vec![("A", "B")]);
+    assert_eq!(matcher.active_variant_name(), Some("B"));
+    assert_eq!(ctx.paints, 1);
+    assert_eq!(ctx.layouts, 1);
+    assert!(ctx.titles.is_empty());
+
+    assert!(!matcher.update_variant(&mut ctx, ABKind::B, ABKind::B));
+    assert_eq!(ctx.variant_changes.len(), 1);
+}
+
+#[test]
+fn sizing() {
+    let bc = BoxConstraints::new(Size::ZERO, Size::new(100.0, 100.0));
+    let size = |present: &mut Presentation, width, height| {
+        let size = present.size(Size::new(width, height), &bc);
+        (size.width, size.height)
+    };
+    let mut present = Presentation::default();
+    assert_eq!(size(&mut present, 50.0, 20.0), (50.0, 20.0));
+    assert_eq!(size(&mut present, 10.0, 10.0), (10.0, 10.0));
+
+    present.sizing = Sizing::Largest;
+    assert_eq!(size(&mut present, 50.0, 20.0), (50.0, 20.0));
+    assert_eq!(size(&mut present, 10.0, 30.0), (50.0, 30.0));
+    assert_eq!(size(&mut present, 200.0, 0.0), (100.0, 30.0));
+}
+
+#[test]
+fn transition() {
+    let mut present = Presentation::default();
+    assert!(!present.start());
+    assert_eq!(present.offset(), 0.0);
+
+    present.transition = Transition::Slide(Duration::from_millis(100));
+    assert!(present.start());
+    assert_eq!(present.offset(), 1.0);
+    assert!(present.advance(0));
+    assert!(present.advance(25_000_000));
+    assert_eq!(present.offset(), 0.75);
+    assert!(!present.advance(75_000_000));
+    assert_eq!(present.offset(), 0.0);
+    assert!(!present.advance(25_000_000));
+
+    let mut matcher = AB::matcher()
+        .with_sizing(Sizing::Largest)
+        .with_transition(Transition::Slide(Duration::from_millis(100)));
+    let mut ctx = RecordedUpdate::default();
+    assert!(!matcher.update_variant(&mut ctx, ABKind::A, ABKind::A));
+    assert_eq!(ctx.anim_frames, 0);
+    assert!(matcher.update_variant(&mut ctx, ABKind::A, ABKind::B));
+    assert_eq!(ctx.anim_frames, 1);
+}
+
+#[test]
+fn titles_and_menu() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    enum Screen {
+        #[matcher(title = "Home")]
+        Home,
+        #[matcher(title = "Details", init = new_a)]
+        Details(A),
+        Other(B),
+    }
+    fn new_a() -> A {
+        A
+    }
+
+    assert_eq!(ScreenKind::Home.title(), Some("Home"));
+    assert_eq!(ScreenKind::Other.title(), None);
+    assert_eq!(ABKind::A.title(), None);
+    let mut ctx = RecordedUpdate::default();
+    Screen::matcher().update_variant(&mut ctx, ScreenKind::Home, ScreenKind::Details);
+    assert_eq!(ctx.titles, vec![Some("Details")]);
+    let _: VariantMenu<Screen> = ScreenKind::menu("View");
+    let _ = ScreenKind::SWITCH_VARIANT.with(ScreenKind::Details);
+
+    fn is_widget(_: impl Widget<Screen>) {}
+    is_widget(Screen::matcher());
+}
+
+#[test]
+fn matcher_trait() {
+    let mut matcher = AB::matcher().a(SizedBox::<A>::empty());
+    assert!(matcher.has_widget_for(ABKind::A));
+    assert_eq!(missing_widgets(&matcher), vec![ABKind::B]);
+
+    let wrong_type = box_widget(SizedBox::<A>::empty());
+    assert!(matcher.set_widget_boxed(ABKind::B, wrong_type).is_err());
+    let right_type = box_widget(SizedBox::<B>::empty());
+    assert!(matcher.set_widget_boxed(ABKind::B, right_type).is_ok());
+    assert!(missing_widgets(&matcher).is_empty());
+}
+
+#[test]
+fn introspection() {
+    let matcher = AB::matcher().a(SizedBox::<A>::empty());
+    assert_eq!(matcher.missing_variants(), vec!["B"]);
+    assert_eq!(matcher.active_variant_name(), None);
+    let error = matcher.try_build().err().unwrap();
+    assert_eq!(error.matcher, "ABMatcher");
+    assert_eq!(error.variants, vec!["B"]);
+
+    let matcher = AB::matcher().a(SizedBox::<A>::empty()).default_empty();
+    assert!(matcher.try_build().is_ok());
+}
+
+#[test]
+fn missing_widgets_strictness() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(missing = "panic")]
+    enum Panic {
+        A(A),
+    }
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(missing = "placeholder")]
+    enum Placeholder {
+        A(A),
+    }
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(missing = "silent", static_dispatch)]
+    enum Silent {
+        A(A),
+    }
+
+    fn is_widget<T: Data>(_: impl Widget<T>) {}
+    is_widget(Panic::matcher());
+    is_widget(Placeholder::matcher());
+    is_widget(Silent::matcher());
+}
+
+#[test]
+#[should_panic(expected = "PanicMatcher::b variant has not been set")]
+fn missing_widget_panics() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(missing = "panic")]
+    enum Panic {
+        A(A),
+        B(B),
+    }
+    let mut matcher = Panic::matcher().a(SizedBox::empty());
+    matcher.check_missing_widgets(WidgetId::next());
+}
+
+#[test]
+fn missing_widget_placeholder() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(missing = "placeholder")]
+    enum Placeholder {
+        A(A),
+        B(B),
+    }
+    let mut matcher = Placeholder::matcher().a(SizedBox::empty());
+    assert!(!matcher.has_widget_for(PlaceholderKind::B));
+    matcher.check_missing_widgets(WidgetId::next());
+    assert!(matcher.has_widget_for(PlaceholderKind::A));
+    assert!(matcher.has_widget_for(PlaceholderKind::B));
+    assert!(matcher.missing_variants().is_empty());
+}
+
+#[test]
+fn replace_widget() {
+    let mut matcher = AB::matcher();
+    let replacement = ReplaceWidget::new(ABKind::B, SizedBox::<B>::empty());
+    assert!(!replacement.apply(|_: OptionKind, widget| Err(widget)));
+    assert!(replacement.apply(|kind, widget| matcher.set_widget_boxed(kind, widget)));
+    assert!(matcher.has_widget_for(ABKind::B));
+    assert!(!replacement.apply(|kind, widget| matcher.set_widget_boxed(kind, widget)));
+
+    let _ = REPLACE_VARIANT_WIDGET.with(ReplaceWidget::new(ABKind::A, SizedBox::<A>::empty()));
+    fn from_controller(matcher: &mut ABMatcher, ctx: &mut EventCtx) {
+        matcher.replace_a(ctx, SizedBox::<A>::empty());
+    }
+    let _ = from_controller;
+}
+
+#[test]
+fn common_lens() {
+    #[derive(Clone, Data)]
+    struct Login {
+        user: String,
+    }
+    #[derive(Clone, Data)]
+    struct Main {
+        user: String,
+        count: u32,
+    }
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(common(user: String = .user))]
+    enum Screen {
+        Login(Login),
+        Main(Main),
+    }
+
+    fn is_lens(_: impl Lens<Screen, String>) {}
+    is_lens(ScreenUserLens);
+
+    let mut screen = Screen::Main(Main {
+        user: "me".to_string(),
+        count: 0,
+    });
+    assert_eq!(Screen::user.get(&screen), "me");
+    Screen::user.put(&mut screen, "you".to_string());
+    assert_eq!(Screen::user.get(&screen), "you");
+    let screen = Screen::Login(Login {
+        user: "them".to_string(),
+    });
+    assert_eq!(Screen::user.get(&screen), "them");
+}
+
+#[test]
+fn full_variant() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    enum Screen {
+        #[matcher(full)]
+        Welcome,
+        Main(A),
+    }
+
+    fn is_widget(_: impl Widget<Screen>) {}
+    is_widget(
+        Screen::matcher()
+            .welcome(SizedBox::<Screen>::empty())
+            .main(SizedBox::<A>::empty()),
+    );
+}
+
+#[test]
+fn context() {
+    #[derive(Clone, Data)]
+    struct Settings;
+
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(context = Settings)]
+    enum Screen {
+        Welcome,
+        Main(A),
+        Split(A, B),
+        #[matcher(full)]
+        About,
+    }
+
+    fn is_widget(_: impl Widget<(Settings, Screen)>) {}
+    is_widget(
+        Screen::matcher()
+            .welcome(SizedBox::<(Settings, ())>::empty())
+            .main(SizedBox::<(Settings, A)>::empty())
+            .split(SizedBox::<(Settings, (A, B))>::empty())
+            .about(SizedBox::<(Settings, Screen)>::empty())
+            .default_empty(),
+    );
+}
+
+#[test]
+fn projections() {
+    #[derive(Clone, Data, druid::Lens)]
+    struct Main {
+        count: u32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    enum Screen {
+        #[matcher(lens = Main::count: u32)]
+        Main(Main),
+        #[matcher(field = 1)]
+        Second(A, B),
+        Both(A, B),
+        #[matcher(field = 0, lens = Main::count: u32)]
+        First(Main, B),
+    }
+
+    fn is_widget(_: impl Widget<Screen>) {}
+    is_widget(
+        Screen::matcher()
+            .main(SizedBox::<u32>::empty())
+            .second(SizedBox::<B>::empty())
+            .both(SizedBox::<(A, B)>::empty())
+            .first(SizedBox::<u32>::empty()),
+    );
+}
+
+/// Data that is always the same, but remembers whether it was cloned.
+#[derive(Debug, PartialEq)]
+struct Cloned(bool);
+
+impl Clone for Cloned {
+    fn clone(&self) -> Self {
+        Cloned(true)
+    }
+}
+
+impl Data for Cloned {
+    fn same(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+/// Edits the data of the child of the active variant.
+struct Edit<F>(F);
+
+impl<F: FnOnce(&mut dyn Any)> VisitChildMut for Edit<F> {
+    type Output = ();
+
+    fn visit_mut<T: Data, W: Widget<T>>(self, _: &mut WidgetPod<T, W>, data: &mut T) {
+        (self.0)(data);
+    }
+}
+
+#[test]
+fn write_back() {
+    #[derive(Clone, Data, druid::Lens)]
+    struct Main {
+        count: u32,
+        tag: Cloned,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    enum Screen {
+        Both(Cloned, u32),
+        #[matcher(field = 1)]
+        Second(Cloned, u32),
+        #[matcher(field = 0)]
+        First(Cloned, u32),
+        #[matcher(lens = Main::count: u32)]
+        Main(Main),
+    }
+
+    let mut matcher = Screen::matcher()
+        .both(SizedBox::empty())
+        .second(SizedBox::empty())
+        .first(SizedBox::empty())
+        .main(SizedBox::empty());
+    let mut data = Screen::Both(Cloned(false), 1);
+    matcher.with_active_child_mut(
+        &mut data,
+        Edit(|data: &mut dyn Any| data.downcast_mut::<(Cloned, u32)>().unwrap().1 = 2),
+    );
+    assert!(matches!(data, Screen::Both(Cloned(false), 2)));
+
+    let mut data = Screen::Second(Cloned(false), 1);
+    matcher.with_active_child_mut(
+        &mut data,
+        Edit(|data: &mut dyn Any| *data.downcast_mut::<u32>().unwrap() = 2),
+    );
+    assert!(matches!(data, Screen::Second(Cloned(false), 2)));
+
+    let mut data = Screen::First(Cloned(false), 1);
+    matcher.with_active_child_mut(&mut data, Edit(|_: &mut dyn Any| ()));
+    assert!(matches!(data, Screen::First(Cloned(false), 1)));
+
+    let mut data = Screen::Main(Main {
+        count: 1,
+        tag: Cloned(false),
+    });
+    matcher.with_active_child_mut(
+        &mut data,
+        Edit(|data: &mut dyn Any| *data.downcast_mut::<u32>().unwrap() = 2),
+    );
+    assert!(matches!(
+        data,
+        Screen::Main(Main {
+            count: 2,
+            tag: Cloned(false)
+        })
+    ));
+
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(context = Cloned)]
+    enum Tagged {
+        #[matcher(lens = Main::tag: Cloned)]
+        Tag(Main),
+        #[matcher(lens = Main::count: u32)]
+        Count(Main),
+    }
+
+    let mut matcher = Tagged::matcher()
+        .tag(SizedBox::empty())
+        .count(SizedBox::empty());
+    let main = || Main {
+        count: 1,
+        tag: Cloned(false),
+    };
+    let mut data = (Cloned(false), Tagged::Tag(main()));
+    matcher.with_active_child_mut(&mut data, Edit(|_: &mut dyn Any| ()));
+    assert_eq!(data.0, Cloned(false));
+    assert!(matches!(
+        data.1,
+        Tagged::Tag(Main {
+            tag: Cloned(false),
+            ..
+        })
+    ));
+
+    let mut data = (Cloned(false), Tagged::Count(main()));
+    matcher.with_active_child_mut(
+        &mut data,
+        Edit(|data: &mut dyn Any| data.downcast_mut::<(Cloned, u32)>().unwrap().1 = 2),
+    );
+    assert_eq!(data.0, Cloned(false));
+    assert!(matches!(data.1, Tagged::Count(Main { count: 2, .. })));
+}
+
+/// Finds the child of the active variant, to tell whether two variants share it.
+struct ChildAddress;
+
+impl VisitChild for ChildAddress {
+    /// The address and the type of the child, as children of different types may share
+    /// an address when they have no size.
+    type Output = (usize, &'static str);
+
+    fn visit<T: Data, W: Widget<T>>(self, child: &mut WidgetPod<T, W>, _: &T) -> Self::Output {
+        let address = child as *mut WidgetPod<T, W> as usize;
+        (address, std::any::type_name::<WidgetPod<T, W>>())
+    }
+}
+
+#[test]
+fn groups() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    enum Status {
+        #[matcher(group = spinner)]
+        Loading,
+        #[matcher(group = spinner)]
+        Refreshing,
+        Ready(A),
+    }
+
+    let matcher = Status::matcher().spinner(SizedBox::<()>::empty());
+    assert!(matcher.has_widget_for(StatusKind::Loading));
+    assert!(matcher.has_widget_for(StatusKind::Refreshing));
+    assert_eq!(matcher.missing_variants(), vec!["Ready"]);
+
+    let mut matcher = matcher.ready(SizedBox::<A>::empty());
+    let loading = matcher.with_active_child(&Status::Loading, ChildAddress);
+    let refreshing = matcher.with_active_child(&Status::Refreshing, ChildAddress);
+    let ready = matcher.with_active_child(&Status::Ready(A), ChildAddress);
+    assert!(loading.is_some());
+    assert_eq!(loading, refreshing);
+    assert_ne!(loading, ready);
+}
+
+#[test]
+fn skipped_variants() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    enum Connection {
+        Online(A),
+        #[matcher(skip)]
+        Reconnecting(B),
+    }
+
+    let matcher = Connection::matcher().online(SizedBox::<A>::empty());
+    assert!(matcher.has_widget_for(ConnectionKind::Reconnecting));
+    assert!(matcher.missing_variants().is_empty());
+
+    fn is_widget(_: impl Widget<Connection>) {}
+    is_widget(matcher.default(SizedBox::empty()));
+}
+
+#[test]
+fn deref_payloads() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    enum Screen {
+        #[matcher(deref)]
+        Main(std::sync::Arc<A>),
+        #[matcher(deref, field = 1)]
+        Detail(u32, std::rc::Rc<B>),
+    }
+
+    fn is_widget(_: impl Widget<Screen>) {}
+    is_widget(
+        Screen::matcher()
+            .main(SizedBox::<A>::empty())
+            .detail(SizedBox::<B>::empty()),
+    );
+
+    let mut payload = std::sync::Arc::new(1);
+    let old = payload.clone();
+    DerefData::with_mut(&mut payload, |_| ());
+    assert!(std::sync::Arc::ptr_eq(&payload, &old));
+    assert!(payload.same(&old));
+
+    DerefData::with_mut(&mut payload, |count| *count += 1);
+    assert!(!std::sync::Arc::ptr_eq(&payload, &old));
+    assert_eq!((*payload, *old), (2, 1));
+}
@@ -0,0 +1,55 @@
+#[cfg(feature = "druid-0-7")]
+extern crate druid_0_7 as druid;
+#[cfg(feature = "druid-0-8")]
+extern crate druid_0_8 as druid;
+
+use druid::{widget::SizedBox, Data, Widget};
+use druid_enums::match_widget;
+
+#[derive(Clone, Data)]
+struct A;
+
+#[derive(Clone, Data)]
+struct B;
+
+#[allow(dead_code)]
+#[derive(Clone, Data)]
+enum Abc {
+    A(A),
+    B(B),
+    C,
+}
+
+#[test]
+fn with_all_variants() {
+    fn inner() -> impl Widget<Abc> {
+        match_widget! { Abc,
+            A(_) => SizedBox::<A>::empty(),
+            B(..) => SizedBox::<B>::empty(),
+            C => SizedBox::<()>::empty(),
+        }
+    }
+    inner();
+}
+
+#[test]
+fn with_wildcard() {
+    fn inner() -> impl Widget<Abc> {
+        match_widget! { Abc,
+            A(..) => SizedBox::<A>::empty(),
+            _ => SizedBox::<Abc>::empty(),
+        }
+    }
+    inner();
+}
+
+#[test]
+fn foreign_enum() {
+    fn inner() -> impl Widget<Option<A>> {
+        match_widget! { Option<A>,
+            Some(_) => SizedBox::<A>::empty(),
+            None => SizedBox::<()>::empty(),
+        }
+    }
+    inner();
+}
@@ -0,0 +1,88 @@
+#[cfg(feature = "druid-0-7")]
+extern crate druid_0_7 as druid;
+#[cfg(feature = "druid-0-8")]
+extern crate druid_0_8 as druid;
+
+use druid::{widget::SizedBox, Data, Widget};
+use druid_enums::{split_route, Matcher, MatcherEnum, VariantMenu};
+
+#[derive(Clone, Data)]
+struct Draft;
+
+#[allow(dead_code)]
+#[derive(Clone, Data, Matcher)]
+enum Mail {
+    Inbox,
+    Sent,
+    Compose(Draft),
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Data, Matcher)]
+enum App {
+    Login,
+    #[matcher(nested, init = inbox)]
+    Mail(Mail),
+}
+
+fn inbox() -> Mail {
+    Mail::Inbox
+}
+
+#[test]
+fn builder() {
+    fn is_widget(_: impl Widget<App>) {}
+    is_widget(App::matcher().login(SizedBox::empty()).mail(|mail| {
+        mail.inbox(SizedBox::empty())
+            .sent(SizedBox::empty())
+            .compose(SizedBox::<Draft>::empty())
+    }));
+}
+
+#[test]
+fn switch_into_nested() {
+    let mut app = App::Login;
+    assert!(app.apply_switch(&MailKind::SWITCH_VARIANT.with(MailKind::Sent)));
+    assert!(matches!(app, App::Mail(Mail::Sent)));
+
+    assert!(!app.apply_switch(&MailKind::SWITCH_VARIANT.with(MailKind::Compose)));
+    assert!(matches!(app, App::Mail(Mail::Sent)));
+
+    assert!(app.apply_switch(&AppKind::SWITCH_VARIANT.with(AppKind::Login)));
+    assert!(matches!(app, App::Login));
+}
+
+#[test]
+fn menu() {
+    let _: VariantMenu<App> = AppKind::menu("View");
+    let _: VariantMenu<App> = <Mail as MatcherEnum>::menu("Mail");
+}
+
+#[test]
+fn routes() {
+    assert_eq!(
+        App::Mail(Mail::Compose(Draft)).route(),
+        vec!["mail", "compose"]
+    );
+    assert_eq!(AppKind::route(&App::Login), vec!["login"]);
+
+    assert!(matches!(
+        App::from_route(&["mail", "sent"]),
+        Some(App::Mail(Mail::Sent))
+    ));
+    assert!(matches!(
+        App::from_route(&["mail"]),
+        Some(App::Mail(Mail::Inbox))
+    ));
+    assert!(App::from_route(&["mail", "compose"]).is_none());
+    assert!(App::from_route(&["login", "sent"]).is_none());
+    assert!(App::from_route(&[]).is_none());
+
+    let mut app = App::Mail(Mail::Compose(Draft));
+    assert!(app.apply_route(&["mail", "compose"]));
+    assert!(matches!(app, App::Mail(Mail::Compose(_))));
+    assert!(app.apply_route(&split_route("/mail/inbox")));
+    assert!(matches!(app, App::Mail(Mail::Inbox)));
+    assert!(!app.apply_route(&["settings"]));
+    assert!(matches!(app, App::Mail(Mail::Inbox)));
+}
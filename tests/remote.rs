@@ -0,0 +1,113 @@
+#[cfg(feature = "druid-0-7")]
+extern crate druid_0_7 as druid;
+#[cfg(feature = "druid-0-8")]
+extern crate druid_0_8 as druid;
+
+use druid::{widget::SizedBox, Data, Widget};
+use druid_enums::{Matcher, OptionMatcher, ResultMatcher};
+
+#[derive(Clone, Data)]
+struct A;
+
+#[allow(dead_code)]
+#[derive(Matcher)]
+#[matcher(remote = "Option<A>")]
+enum OptionA {
+    Some(A),
+    None,
+}
+
+#[allow(dead_code)]
+#[derive(Matcher)]
+#[matcher(remote = "Result<A, String>", matcher_name = ResultAMatcher)]
+enum ResultA {
+    Ok(A),
+    Err(String),
+}
+
+#[test]
+fn option() {
+    fn inner() -> impl Widget<Option<A>> {
+        OptionA::matcher()
+            .some(SizedBox::<A>::empty())
+            .none(SizedBox::<()>::empty())
+    }
+    inner();
+}
+
+#[test]
+fn result() {
+    fn inner() -> impl Widget<Result<A, String>> {
+        ResultAMatcher::new()
+            .ok(SizedBox::<A>::empty())
+            .err(SizedBox::<String>::empty())
+    }
+    inner();
+}
+
+#[test]
+fn ready_made() {
+    fn option() -> impl Widget<Option<A>> {
+        OptionMatcher::new()
+            .some(SizedBox::<A>::empty())
+            .none(SizedBox::<()>::empty())
+    }
+    fn result() -> impl Widget<Result<A, String>> {
+        ResultMatcher::new()
+            .ok(SizedBox::<A>::empty())
+            .err(SizedBox::<String>::empty())
+    }
+    option();
+    result();
+}
+
+mod facade {
+    pub(crate) use druid as ui;
+    pub(crate) use druid_enums as enums;
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Data, Matcher)]
+#[matcher(druid = "facade::ui")]
+enum Facade {
+    A(A),
+}
+
+#[test]
+fn druid_path() {
+    fn inner() -> impl Widget<Facade> {
+        Facade::matcher().a(SizedBox::<A>::empty())
+    }
+    inner();
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Data, facade::enums::Matcher)]
+#[matcher(druid = "facade::ui", crate = "facade::enums")]
+enum CrateFacade {
+    A(A),
+    B,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Data, facade::enums::Matcher)]
+#[matcher(druid = "facade::ui", crate = "facade::enums", static_dispatch)]
+enum StaticFacade {
+    A(A),
+}
+
+#[test]
+fn crate_path() {
+    fn inner() -> impl Widget<CrateFacade> {
+        CrateFacade::matcher().a(SizedBox::<A>::empty())
+    }
+    inner();
+
+    fn is_widget(_: impl Widget<StaticFacade>) {}
+    is_widget(StaticFacade::matcher().a(SizedBox::<A>::empty()));
+    is_widget(facade::enums::match_widget! {
+        #[matcher(crate = "facade::enums")]
+        StaticFacade,
+        A(_) => SizedBox::<A>::empty(),
+    });
+}
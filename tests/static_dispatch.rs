@@ -0,0 +1,151 @@
+#[cfg(feature = "druid-0-7")]
+extern crate druid_0_7 as druid;
+#[cfg(feature = "druid-0-8")]
+extern crate druid_0_8 as druid;
+
+use druid::{
+    widget::{Label, SizedBox},
+    Data, Widget, WidgetPod,
+};
+use druid_enums::{__private::VisitChild, missing_widgets, Matcher, Sizing, Transition, Unset};
+
+#[derive(Clone, Data)]
+struct A;
+
+#[derive(Clone, Data)]
+struct B;
+
+#[allow(dead_code)]
+#[derive(Clone, Data, Matcher)]
+#[matcher(static_dispatch)]
+enum AB {
+    A(A),
+    B(B),
+    C,
+}
+
+#[test]
+fn return_type() {
+    fn inner() -> ABMatcher<SizedBox<A>, SizedBox<B>, Label<()>> {
+        AB::matcher()
+            .with_sizing(Sizing::Largest)
+            .a(SizedBox::empty())
+            .b(SizedBox::empty())
+            .c(Label::new("C"))
+            .with_transition(Transition::Instant)
+    }
+    fn is_widget(_: impl Widget<AB>) {}
+    is_widget(inner());
+}
+
+#[test]
+fn unset_variants() {
+    let matcher: ABMatcher<SizedBox<A>, Unset, Unset> = ABMatcher::new().a(SizedBox::empty());
+    assert!(matcher.has_widget_for(ABKind::A));
+    assert_eq!(missing_widgets(&matcher), vec![ABKind::B, ABKind::C]);
+    assert_eq!(matcher.missing_variants(), vec!["B", "C"]);
+    assert!(matcher.try_build().is_err());
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Data, Matcher)]
+#[matcher(static_dispatch, matcher_name = Generic)]
+enum GenericEnum<T> {
+    Value(T),
+    Nothing,
+}
+
+#[test]
+fn generic() {
+    fn is_widget<T: Data>(_: impl Widget<GenericEnum<T>>) {}
+    is_widget(
+        GenericEnum::<u32>::matcher()
+            .value(SizedBox::empty())
+            .nothing(SizedBox::empty()),
+    );
+}
+
+#[test]
+fn full_variant() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(static_dispatch)]
+    enum Screen {
+        #[matcher(full)]
+        Welcome,
+        Main(A),
+    }
+
+    let _: ScreenMatcher<SizedBox<Screen>, Unset> =
+        Screen::matcher().welcome(SizedBox::<Screen>::empty());
+}
+
+/// Finds the child of the active variant, to tell whether two variants share it.
+struct ChildAddress;
+
+impl VisitChild for ChildAddress {
+    /// The address and the type of the child, as children of different types may share
+    /// an address when they have no size.
+    type Output = (usize, &'static str);
+
+    fn visit<T: Data, W: Widget<T>>(self, child: &mut WidgetPod<T, W>, _: &T) -> Self::Output {
+        let address = child as *mut WidgetPod<T, W> as usize;
+        (address, std::any::type_name::<WidgetPod<T, W>>())
+    }
+}
+
+#[test]
+fn groups() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(static_dispatch)]
+    enum Status {
+        #[matcher(group = spinner)]
+        Loading,
+        Ready(A),
+        #[matcher(group = spinner)]
+        Refreshing,
+    }
+
+    let matcher: StatusMatcher<SizedBox<()>, Unset> =
+        Status::matcher().spinner(SizedBox::<()>::empty());
+    assert_eq!(missing_widgets(&matcher), vec![StatusKind::Ready]);
+
+    let mut matcher = matcher.ready(SizedBox::<A>::empty());
+    let loading = matcher.with_active_child(&Status::Loading, ChildAddress);
+    let refreshing = matcher.with_active_child(&Status::Refreshing, ChildAddress);
+    let ready = matcher.with_active_child(&Status::Ready(A), ChildAddress);
+    assert!(loading.is_some());
+    assert_eq!(loading, refreshing);
+    assert_ne!(loading, ready);
+}
+
+#[test]
+fn skipped_variants() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(static_dispatch)]
+    enum Connection {
+        Online(A),
+        #[matcher(skip)]
+        Reconnecting(B),
+    }
+
+    let matcher: ConnectionMatcher<SizedBox<A>> = Connection::matcher().online(SizedBox::empty());
+    assert!(missing_widgets(&matcher).is_empty());
+}
+
+#[test]
+fn deref_payloads() {
+    #[allow(dead_code)]
+    #[derive(Clone, Data, Matcher)]
+    #[matcher(static_dispatch)]
+    enum Screen {
+        #[matcher(deref)]
+        Main(std::sync::Arc<A>),
+        Other(B),
+    }
+
+    let matcher: ScreenMatcher<SizedBox<A>, Unset> = Screen::matcher().main(SizedBox::empty());
+    assert_eq!(missing_widgets(&matcher), vec![ScreenKind::Other]);
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "tracing")]
+
+#[cfg(feature = "druid-0-7")]
+extern crate druid_0_7 as druid;
+#[cfg(feature = "druid-0-8")]
+extern crate druid_0_8 as druid;
+
+use druid::{widget::SizedBox, Data, Widget, WidgetId};
+use druid_enums::{
+    __private::{variant_span, variant_switched, widget_missing},
+    Matcher,
+};
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+#[allow(dead_code)]
+#[derive(Clone, Data, PartialEq, Matcher)]
+enum Screen {
+    Login,
+    Main,
+}
+
+/// Writes every span and event down as its fields, e.g. `method=layout variant=Main`.
+#[derive(Clone, Default)]
+struct Recorder {
+    records: Arc<Mutex<Vec<String>>>,
+}
+
+struct Fields(Vec<String>);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push(format!("{}={:?}", field.name(), value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push(format!("{}={}", field.name(), value));
+    }
+}
+
+impl Recorder {
+    fn push(&self, kind: &str, record: impl FnOnce(&mut Fields)) {
+        let mut fields = Fields(vec![kind.to_string()]);
+        record(&mut fields);
+        self.records.lock().unwrap().push(fields.0.join(" "));
+    }
+
+    fn records(&self) -> Vec<String> {
+        self.records.lock().unwrap().clone()
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes) -> span::Id {
+        self.push("span", |fields| span.record(fields));
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event) {
+        let level = event.metadata().level().to_string().to_lowercase();
+        self.push(&level, |fields| event.record(fields));
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[test]
+fn instrumentation() {
+    fn is_widget(_: impl Widget<Screen>) {}
+    is_widget(
+        Screen::matcher()
+            .login(SizedBox::empty())
+            .main(SizedBox::empty()),
+    );
+
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let _span = variant_span("ScreenMatcher", "layout", Some(ScreenKind::Main));
+        variant_switched("ScreenMatcher", ScreenKind::Login, ScreenKind::Main);
+        widget_missing("ScreenMatcher", "main", WidgetId::next());
+    });
+
+    let records = recorder.records();
+    assert_eq!(records.len(), 3);
+    assert_eq!(
+        records[0],
+        "span matcher=ScreenMatcher method=layout variant=Main"
+    );
+    assert!(records[1].starts_with("debug "));
+    assert!(records[1].contains("old=Login new=Main"));
+    assert!(records[2].starts_with("warn "));
+    assert!(records[2].contains("builder=main"));
+}
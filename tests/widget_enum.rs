@@ -0,0 +1,37 @@
+#[cfg(feature = "druid-0-7")]
+extern crate druid_0_7 as druid;
+#[cfg(feature = "druid-0-8")]
+extern crate druid_0_8 as druid;
+
+use druid::{
+    widget::{Label, SizedBox, Spinner},
+    Widget,
+};
+use druid_enums::WidgetEnum;
+
+#[allow(dead_code)]
+#[derive(WidgetEnum)]
+enum Body {
+    Text(Label<String>),
+    Empty(SizedBox<String>),
+}
+
+#[test]
+fn concrete() {
+    fn is_widget(_: impl Widget<String>) {}
+    is_widget(Body::Text(Label::new("text")));
+}
+
+#[allow(dead_code)]
+#[derive(WidgetEnum)]
+enum Status<W> {
+    Loading(Spinner),
+    Done(W),
+}
+
+#[test]
+fn generic() {
+    fn is_widget<T>(_: impl Widget<T>) {}
+    is_widget::<bool>(Status::<SizedBox<bool>>::Loading(Spinner::new()));
+    is_widget::<u32>(Status::Done(Label::<u32>::new("done")));
+}
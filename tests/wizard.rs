@@ -0,0 +1,56 @@
+#[cfg(feature = "druid-0-7")]
+extern crate druid_0_7 as druid;
+#[cfg(feature = "druid-0-8")]
+extern crate druid_0_8 as druid;
+
+use druid::{widget::SizedBox, Data, Widget};
+use druid_enums::{Matcher, StepIndicator, Wizard};
+
+#[derive(Clone, Data, Default)]
+struct Account {
+    name: String,
+}
+
+fn has_name(account: &Account) -> bool {
+    !account.name.is_empty()
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Data, Matcher)]
+#[matcher(wizard)]
+enum Setup {
+    Welcome,
+    #[matcher(init = Account::default, validate = has_name)]
+    Account(Account),
+    Done,
+}
+
+#[test]
+fn steps() {
+    let mut setup = Setup::Welcome;
+    assert_eq!(setup.step_index(), 0);
+    assert_eq!(Setup::step_count(), 3);
+    assert!(!SetupKind::previous_step(&mut setup));
+
+    assert!(SetupKind::next_step(&mut setup));
+    assert_eq!(SetupKind::of(&setup).step_index(), 1);
+    assert!(!SetupKind::next_step(&mut setup));
+
+    if let Setup::Account(account) = &mut setup {
+        account.name = "druid".to_string();
+    }
+    assert!(SetupKind::next_step(&mut setup));
+    assert!(matches!(setup, Setup::Done));
+    assert!(!SetupKind::next_step(&mut setup));
+
+    assert!(SetupKind::previous_step(&mut setup));
+    assert!(matches!(setup, Setup::Account(_)));
+}
+
+#[test]
+fn widgets() {
+    fn is_widget(_: impl Widget<Setup>) {}
+    is_widget(Setup::matcher().welcome(SizedBox::empty()));
+    is_widget(StepIndicator);
+    let _ = (SetupKind::NEXT_STEP, SetupKind::PREVIOUS_STEP);
+}